@@ -41,7 +41,15 @@ pub fn impl_tuples(tokens: TokenStream) -> TokenStream {
                     #t_n: 'static + Introspect,
                 )*
             {
-                fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, mut visit: F)
+                fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, visit: F)
+                where
+                    F: FnMut(&Breadcrumbs, &dyn Any),
+                {
+                    self.introspect_from_with(&Options::default(), breadcrumbs, visit);
+                }
+
+                #[allow(unused_variables)]
+                fn introspect_from_with<F>(&self, options: &Options, breadcrumbs: Breadcrumbs, mut visit: F)
                 where
                     F: FnMut(&Breadcrumbs, &dyn Any),
                 {
@@ -50,7 +58,7 @@ pub fn impl_tuples(tokens: TokenStream) -> TokenStream {
                     #({
                         let mut breadcrumbs = breadcrumbs.clone();
                         breadcrumbs.push_back(Breadcrumb::TupleIndex(#idx));
-                        self.#idx.introspect_from(breadcrumbs, &mut visit);
+                        self.#idx.introspect_from_with(options, breadcrumbs, &mut visit);
                     })*
                 }
             }
@@ -13,11 +13,53 @@ pub fn derive_spectacle(input: proc_macro::TokenStream) -> proc_macro::TokenStre
     let input = parse_macro_input!(input as DeriveInput);
 
     let name = input.ident;
+    let type_info_generics = add_type_info_bounds(input.generics.clone());
     let generics = add_trait_bounds(input.generics);
 
     let out = match input.data {
-        syn::Data::Struct(data) => impl_introspect_struct(&name, &generics, &data.fields),
-        syn::Data::Enum(data) => impl_introspect_enum(&name, &generics, &data.variants),
+        syn::Data::Struct(data) => {
+            let introspect = impl_introspect_struct(&name, &generics, &data.fields);
+            let fields = describe_fields(&data.fields);
+            let type_info = impl_type_info(
+                &name,
+                &type_info_generics,
+                quote!(spectacle::type_info::TypeKind::Struct(#fields)),
+                TokenStream::new(),
+            );
+            quote!(#introspect #type_info)
+        }
+        syn::Data::Enum(data) => {
+            let introspect = impl_introspect_enum(&name, &generics, &data.variants);
+            let variants = data.variants.iter().map(|variant| {
+                let name = syn::LitStr::new(&variant.ident.to_string(), variant.ident.span());
+                let fields = describe_fields(&variant.fields);
+                quote! {
+                    spectacle::type_info::Variant {
+                        name: #name,
+                        fields: #fields,
+                    }
+                }
+            });
+            let names = data.variants.iter().map(|variant| {
+                let ident = &variant.ident;
+                let name = syn::LitStr::new(&ident.to_string(), ident.span());
+                quote!(Self::#ident { .. } => Some(#name))
+            });
+            // the active variant is found by downcasting, which requires `'static`
+            let type_info = impl_type_info(
+                &name,
+                &add_static_bounds(type_info_generics),
+                quote!(spectacle::type_info::TypeKind::Enum(vec![#( #variants ),*])),
+                quote! {
+                    .with_active_variant(spectacle::type_info::ActiveVariant(|value| {
+                        match *value.downcast_ref::<Self>()? {
+                            #( #names ),*
+                        }
+                    }))
+                },
+            );
+            quote!(#introspect #type_info)
+        }
         syn::Data::Union(_) => {
             emit_error!(
                 name.span(),
@@ -31,6 +73,29 @@ pub fn derive_spectacle(input: proc_macro::TokenStream) -> proc_macro::TokenStre
     out.into()
 }
 
+#[proc_macro_derive(Navigate)]
+#[proc_macro_error]
+pub fn derive_navigate(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let name = input.ident;
+    let generics = add_navigate_bounds(input.generics);
+
+    let out = match input.data {
+        syn::Data::Struct(data) => impl_navigate_struct(&name, &generics, &data.fields),
+        syn::Data::Enum(data) => impl_navigate_enum(&name, &generics, &data.variants),
+        syn::Data::Union(_) => {
+            emit_error!(
+                name.span(),
+                "Navigate can only be derived for structs and enums"
+            );
+
+            TokenStream::new()
+        }
+    };
+    out.into()
+}
+
 // Add a bound `T: 'static + Introspect` to every type parameter T.
 fn add_trait_bounds(mut generics: Generics) -> Generics {
     for param in &mut generics.params {
@@ -42,6 +107,38 @@ fn add_trait_bounds(mut generics: Generics) -> Generics {
     generics
 }
 
+// Add a bound `T: TypeInfo` to every type parameter T.
+fn add_type_info_bounds(mut generics: Generics) -> Generics {
+    for param in &mut generics.params {
+        if let GenericParam::Type(ref mut type_param) = *param {
+            type_param.bounds.push(parse_quote!(spectacle::TypeInfo));
+        }
+    }
+    generics
+}
+
+// Add a bound `T: 'static` to every type parameter T.
+fn add_static_bounds(mut generics: Generics) -> Generics {
+    for param in &mut generics.params {
+        if let GenericParam::Type(ref mut type_param) = *param {
+            type_param.bounds.push(parse_quote!('static));
+        }
+    }
+    generics
+}
+
+// Add a bound `T: Navigate` to every type parameter T.
+fn add_navigate_bounds(mut generics: Generics) -> Generics {
+    for param in &mut generics.params {
+        if let GenericParam::Type(ref mut type_param) = *param {
+            type_param
+                .bounds
+                .push(parse_quote!(spectacle::path::Navigate));
+        }
+    }
+    generics
+}
+
 // Create an unused generic identifier
 fn create_generic_ident(generics: &Generics) -> Ident {
     let mut ident = Ident::new("F", generics.span());
@@ -53,7 +150,7 @@ fn create_generic_ident(generics: &Generics) -> Ident {
     }) {
         ident = Ident::new(&format!("F{}", n), generics.span());
         n += 1;
-        if n == std::u8::MAX {
+        if n == u8::MAX {
             emit_error!(
                 generics,
                 "could not generate an appropriate unused type parameter";
@@ -67,7 +164,7 @@ fn create_generic_ident(generics: &Generics) -> Ident {
 
 fn impl_introspect_struct(name: &Ident, generics: &Generics, fields: &Fields) -> TokenStream {
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-    let f = create_generic_ident(&generics);
+    let f = create_generic_ident(generics);
     let field_names: Vec<_> = fields
         .iter()
         .enumerate()
@@ -85,7 +182,25 @@ fn impl_introspect_struct(name: &Ident, generics: &Generics, fields: &Fields) ->
     quote! {
         impl #impl_generics spectacle::Introspect for #name #ty_generics #where_clause
         {
-            fn introspect_from<#f>(&self, breadcrumbs: spectacle::Breadcrumbs, mut visit: #f)
+            fn introspect_from<#f>(&self, breadcrumbs: spectacle::Breadcrumbs, visit: #f)
+            where
+                #f: FnMut(&spectacle::Breadcrumbs, &dyn std::any::Any),
+            {
+                spectacle::Introspect::introspect_from_with(
+                    self,
+                    &spectacle::Options::default(),
+                    breadcrumbs,
+                    visit,
+                );
+            }
+
+            #[allow(unused_variables)]
+            fn introspect_from_with<#f>(
+                &self,
+                options: &spectacle::Options,
+                breadcrumbs: spectacle::Breadcrumbs,
+                mut visit: #f,
+            )
             where
                 #f: FnMut(&spectacle::Breadcrumbs, &dyn std::any::Any),
             {
@@ -97,6 +212,64 @@ fn impl_introspect_struct(name: &Ident, generics: &Generics, fields: &Fields) ->
     }
 }
 
+fn impl_type_info(
+    name: &Ident,
+    generics: &Generics,
+    kind: TokenStream,
+    with: TokenStream,
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let type_params = generics.type_params().map(|param| &param.ident);
+
+    quote! {
+        impl #impl_generics spectacle::TypeInfo for #name #ty_generics #where_clause
+        {
+            fn type_info() -> spectacle::type_info::Type {
+                spectacle::type_info::Type::new::<Self>(#kind).with_generics(vec![
+                    #( spectacle::type_info::TypeRef::of::<#type_params>() ),*
+                ])#with
+            }
+        }
+    }
+}
+
+// describe the fields of a struct or variant as a `spectacle::type_info::Fields`
+fn describe_fields(fields: &Fields) -> TokenStream {
+    match fields {
+        Fields::Unit => quote!(spectacle::type_info::Fields::Unit),
+        Fields::Named(fields) => {
+            let fields = fields.named.iter().map(|field| {
+                let name = field.ident.clone().expect("named fields have names");
+                let name_lit = syn::LitStr::new(&name.to_string(), field.span());
+                let ty = field_type(&field.ty);
+                quote! {
+                    spectacle::type_info::Field {
+                        name: #name_lit,
+                        ty: #ty,
+                    }
+                }
+            });
+            quote!(spectacle::type_info::Fields::Named(vec![#( #fields ),*]))
+        }
+        Fields::Unnamed(fields) => {
+            let types = fields.unnamed.iter().map(|field| field_type(&field.ty));
+            quote! {
+                spectacle::type_info::Fields::Unnamed(vec![#( #types ),*])
+            }
+        }
+    }
+}
+
+// refer to the type of a field, which is described as opaque if it does not
+// implement `TypeInfo`, as a field which only implements `Introspect` may not
+fn field_type(ty: &Type) -> TokenStream {
+    quote! {{
+        #[allow(unused_imports)]
+        use spectacle::type_info::{DescribedField as _, OpaqueField as _};
+        (&spectacle::type_info::FieldType::<#ty>::new()).type_ref()
+    }}
+}
+
 // TODO: more fine-grained control of field visibility somehow
 // for now, we'll visit all fields, even private ones
 fn recurse_fields<Accessor>(
@@ -121,7 +294,7 @@ where
                     let mut breadcrumbs = breadcrumbs.clone();
                     #inject_breadcrumb
                     breadcrumbs.push_back(spectacle::Breadcrumb::Field(#name_lit));
-                    spectacle::Introspect::introspect_from(&#field, breadcrumbs, &mut visit);
+                    spectacle::Introspect::introspect_from_with(&#field, options, breadcrumbs, &mut visit);
                 }}
             });
 
@@ -135,7 +308,7 @@ where
                     let mut breadcrumbs = breadcrumbs.clone();
                     #inject_breadcrumb
                     breadcrumbs.push_back(spectacle::Breadcrumb::TupleIndex(#idx));
-                    spectacle::Introspect::introspect_from(&#field, breadcrumbs, &mut visit);
+                    spectacle::Introspect::introspect_from_with(&#field, options, breadcrumbs, &mut visit);
                 }}
             });
 
@@ -150,13 +323,31 @@ fn impl_introspect_enum(
     variants: &Punctuated<Variant, Comma>,
 ) -> TokenStream {
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-    let f = create_generic_ident(&generics);
+    let f = create_generic_ident(generics);
     let recurse = recurse_variants(variants);
 
     quote! {
         impl #impl_generics spectacle::Introspect for #name #ty_generics #where_clause
         {
-            fn introspect_from<#f>(&self, breadcrumbs: spectacle::Breadcrumbs, mut visit: #f)
+            fn introspect_from<#f>(&self, breadcrumbs: spectacle::Breadcrumbs, visit: #f)
+            where
+                #f: FnMut(&spectacle::Breadcrumbs, &dyn std::any::Any),
+            {
+                spectacle::Introspect::introspect_from_with(
+                    self,
+                    &spectacle::Options::default(),
+                    breadcrumbs,
+                    visit,
+                );
+            }
+
+            #[allow(unused_variables)]
+            fn introspect_from_with<#f>(
+                &self,
+                options: &spectacle::Options,
+                breadcrumbs: spectacle::Breadcrumbs,
+                mut visit: #f,
+            )
             where
                 #f: FnMut(&spectacle::Breadcrumbs, &dyn std::any::Any),
             {
@@ -255,3 +446,205 @@ fn recurse_variants(variants: &Punctuated<Variant, Comma>) -> Vec<TokenStream> {
         })
         .collect()
 }
+
+// the breadcrumb which leads to a field, as a pattern
+fn field_breadcrumb(idx: usize, field: &syn::Field) -> TokenStream {
+    match field.ident {
+        Some(ref name) => {
+            let name_lit = syn::LitStr::new(&name.to_string(), field.span());
+            quote!(spectacle::Breadcrumb::Field(#name_lit))
+        }
+        None => quote!(spectacle::Breadcrumb::TupleIndex(#idx)),
+    }
+}
+
+// Implement `Navigate` with `child` and `child_mut` methods sharing a body,
+// which refers to the child by `reference`: `&` or `&mut`, and any other
+// `methods`.
+fn impl_navigate(
+    name: &Ident,
+    generics: &Generics,
+    methods: TokenStream,
+    body: impl Fn(TokenStream) -> TokenStream,
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let child = body(quote!(&));
+    let child_mut = body(quote!(&mut));
+
+    quote! {
+        impl #impl_generics spectacle::path::Navigate for #name #ty_generics #where_clause
+        {
+            #[allow(unreachable_code)]
+            fn child(
+                &self,
+                path: &[spectacle::Breadcrumb],
+            ) -> Result<(usize, &dyn spectacle::path::Navigate), spectacle::path::PathErrorKind>
+            {
+                #child
+            }
+
+            #[allow(unreachable_code)]
+            fn child_mut(
+                &mut self,
+                path: &[spectacle::Breadcrumb],
+            ) -> Result<(usize, &mut dyn spectacle::path::Navigate), spectacle::path::PathErrorKind>
+            {
+                #child_mut
+            }
+
+            #methods
+        }
+    }
+}
+
+fn impl_navigate_struct(name: &Ident, generics: &Generics, fields: &Fields) -> TokenStream {
+    impl_navigate(name, generics, TokenStream::new(), |reference| {
+        let arms = fields.iter().enumerate().map(|(idx, field)| {
+            let breadcrumb = field_breadcrumb(idx, field);
+            let access = match field.ident {
+                Some(ref name) => quote!(self.#name),
+                None => {
+                    let idx = Index::from(idx);
+                    quote!(self.#idx)
+                }
+            };
+            quote! {
+                Some(#breadcrumb) => Ok((1, #reference #access)),
+            }
+        });
+
+        quote! {
+            match path.first() {
+                #( #arms )*
+                _ => Err(spectacle::path::PathErrorKind::not_found(path)),
+            }
+        }
+    })
+}
+
+// A path into an enum begins with the breadcrumb of the active variant,
+// followed by that of one of its fields.
+fn impl_navigate_enum(
+    name: &Ident,
+    generics: &Generics,
+    variants: &Punctuated<Variant, Comma>,
+) -> TokenStream {
+    let names: Vec<_> = variants
+        .iter()
+        .map(|variant| {
+            let name = &variant.ident;
+            let name_lit = syn::LitStr::new(&name.to_string(), name.span());
+            quote!(Self::#name { .. } => #name_lit,)
+        })
+        .collect();
+
+    let set_variant = impl_set_variant(variants);
+
+    impl_navigate(name, generics, set_variant, |_| {
+        let arms = variants.iter().flat_map(|variant| {
+            let name = &variant.ident;
+            let name_lit = syn::LitStr::new(&name.to_string(), name.span());
+            let n_fields = variant.fields.len();
+            variant.fields.iter().enumerate().map(move |(idx, field)| {
+                let breadcrumb = field_breadcrumb(idx, field);
+                let pattern = match field.ident {
+                    Some(ref field_name) => quote!(Self::#name { #field_name: field, .. }),
+                    None => {
+                        let fields = (0..n_fields).map(|other| {
+                            if other == idx {
+                                quote!(field)
+                            } else {
+                                quote!(_)
+                            }
+                        });
+                        quote!(Self::#name(#( #fields ),*))
+                    }
+                };
+                quote! {
+                    (#pattern, [spectacle::Breadcrumb::Variant(#name_lit), #breadcrumb, ..]) => {
+                        Ok((2, field))
+                    }
+                }
+            })
+        });
+
+        quote! {
+            match (self, path) {
+                #( #arms )*
+                (this, path) => {
+                    let active = match *this {
+                        #( #names )*
+                    };
+                    Err(spectacle::path::PathErrorKind::for_variant(path, active))
+                }
+            }
+        }
+    })
+}
+
+// Implement `Navigate::set_variant` for the variants with no fields or one.
+fn impl_set_variant(variants: &Punctuated<Variant, Comma>) -> TokenStream {
+    let arms = variants.iter().map(|variant| {
+        let name = &variant.ident;
+        let name_lit = syn::LitStr::new(&name.to_string(), name.span());
+        let field = match variant.fields.iter().collect::<Vec<_>>().as_slice() {
+            [] => {
+                let construct = match variant.fields {
+                    Fields::Named(_) => quote!(Self::#name {}),
+                    Fields::Unnamed(_) => quote!(Self::#name()),
+                    Fields::Unit => quote!(Self::#name),
+                };
+                return quote! {
+                    (#name_lit, None) => {
+                        *self = #construct;
+                        Ok(())
+                    }
+                };
+            }
+            [field] => *field,
+            fields => {
+                let count = fields.len();
+                return quote! {
+                    (#name_lit, _) => Err(spectacle::path::PathErrorKind::UnsupportedVariant {
+                        type_name: std::any::type_name::<Self>(),
+                        variant: #name_lit,
+                        fields: #count,
+                    }),
+                };
+            }
+        };
+        let ty = &field.ty;
+        let construct = match field.ident {
+            Some(ref field_name) => quote!(Self::#name { #field_name: *value }),
+            None => quote!(Self::#name(*value)),
+        };
+        quote! {
+            (#name_lit, Some(value)) => match value.downcast::<#ty>() {
+                Ok(value) => {
+                    *self = #construct;
+                    Ok(())
+                }
+                Err(_) => Err(spectacle::path::PathErrorKind::TypeMismatch {
+                    expected: std::any::type_name::<#ty>(),
+                }),
+            },
+        }
+    });
+
+    quote! {
+        fn set_variant(
+            &mut self,
+            variant: &str,
+            value: Option<Box<dyn std::any::Any>>,
+        ) -> Result<(), spectacle::path::PathErrorKind> {
+            match (variant, value) {
+                #( #arms )*
+                (variant, value) => Err(spectacle::path::PathErrorKind::UnknownVariant {
+                    type_name: std::any::type_name::<Self>(),
+                    variant: variant.to_string(),
+                    fields: value.is_some() as usize,
+                }),
+            }
+        }
+    }
+}
@@ -0,0 +1,318 @@
+//! Explore JSON and JSON Lines documents with spectacle.
+
+use serde_json::Value;
+use spectacle::{
+    json::{
+        codegen::Generator,
+        infer::{Kind, Schema},
+        stream::introspect_documents,
+        JsonPath, Segment,
+    },
+    Breadcrumbs, Options,
+};
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, ErrorKind, Read, Write},
+    process,
+};
+
+const USAGE: &str = "\
+Explore JSON and JSON Lines documents.
+
+USAGE:
+    spectacle <COMMAND> [OPTIONS] [FILE]...
+
+Reads each FILE, or standard input if there are none or FILE is `-`. Every
+top-level JSON value in the input is a document, so JSON Lines work as well as
+ordinary JSON. Documents are streamed rather than read into memory, except for
+the nodes printed by `filter`.
+
+COMMANDS:
+    paths              Print the path, kind and value of every node
+    filter <PATTERN>   Print the path and value of every node whose path
+                       matches PATTERN, in which `*` matches any text within
+                       a single key or index, and `**` matches any text
+    summary            Print the kinds observed at each path, over all documents
+    types              Print Rust types inferred from all documents
+
+OPTIONS:
+    --root <NAME>      Name of the root type generated by `types` [default: Root]
+    -h, --help         Print this message
+";
+
+enum Command {
+    Paths,
+    Filter(String),
+    Summary,
+    Types { root: String },
+}
+
+struct Args {
+    command: Command,
+    files: Vec<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = std::env::args().skip(1);
+    let mut command = match args.next().as_deref() {
+        None | Some("-h") | Some("--help") => {
+            print!("{}", USAGE);
+            process::exit(0);
+        }
+        Some("paths") => Command::Paths,
+        Some("filter") => Command::Filter(args.next().ok_or("filter requires a PATTERN")?),
+        Some("summary") => Command::Summary,
+        Some("types") => Command::Types {
+            root: "Root".to_string(),
+        },
+        Some(other) => return Err(format!("unknown command: {}", other)),
+    };
+
+    let mut files = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print!("{}", USAGE);
+                process::exit(0);
+            }
+            "--root" => match &mut command {
+                Command::Types { root } => {
+                    *root = args.next().ok_or("--root requires a NAME")?;
+                }
+                _ => return Err("--root only applies to `types`".to_string()),
+            },
+            flag if flag.starts_with("--") => return Err(format!("unknown option: {}", flag)),
+            file => files.push(file.to_string()),
+        }
+    }
+    if files.is_empty() {
+        files.push("-".to_string());
+    }
+
+    Ok(Args { command, files })
+}
+
+// open each file in turn, standing for standard input if it is `-`
+fn for_each_file<F>(files: &[String], mut f: F) -> io::Result<()>
+where
+    F: FnMut(&str, Box<dyn Read>) -> io::Result<()>,
+{
+    for file in files {
+        let reader: Box<dyn Read> = if file == "-" {
+            Box::new(io::stdin())
+        } else {
+            Box::new(File::open(file).map_err(|err| located(file, err))?)
+        };
+        f(file, Box::new(BufReader::new(reader)))?;
+    }
+    Ok(())
+}
+
+// call `f` with the path of each node in each document in each file, and the
+// node itself; arrays and objects are streamed, so are always empty
+fn for_each_node<F>(files: &[String], mut f: F) -> io::Result<()>
+where
+    F: FnMut(&Breadcrumbs, &Value) -> io::Result<()>,
+{
+    for_each_file(files, |file, reader| {
+        let mut result = Ok(());
+        introspect_documents(reader, &Options::default(), |breadcrumbs, visit| {
+            if let (Ok(()), Some(value)) = (&result, visit.downcast_ref::<Value>()) {
+                result = f(breadcrumbs, value);
+            }
+        })
+        .map_err(|err| located(file, err.into()))?;
+        result
+    })
+}
+
+// prefix an input error with the file it occurred in
+fn located(file: &str, err: io::Error) -> io::Error {
+    io::Error::new(err.kind(), format!("{}: {}", file, err))
+}
+
+// match `text` against `pattern`, in which `**` matches any sequence of
+// characters, and `*` any sequence which does not cross into another segment
+fn matches(pattern: &str, text: &str) -> bool {
+    match pattern.find('*') {
+        None => pattern == text,
+        Some(star) => {
+            let prefix = &pattern[..star];
+            let (rest, within_segment) = match pattern[star..].strip_prefix("**") {
+                Some(rest) => (rest, false),
+                None => (&pattern[star + 1..], true),
+            };
+            if !text.starts_with(prefix) {
+                return false;
+            }
+            let text = &text[prefix.len()..];
+            let end = if within_segment {
+                text.find(&['.', '[', ']'][..]).unwrap_or(text.len())
+            } else {
+                text.len()
+            };
+            (0..=end)
+                .filter(|&idx| text.is_char_boundary(idx))
+                .any(|idx| matches(rest, &text[idx..]))
+        }
+    }
+}
+
+// The nodes matched by `filter`, whose values are rebuilt from the nodes
+// streamed within them before they are printed.
+struct Matches<W> {
+    out: W,
+    // the breadcrumbs, path and value so far of each match still being
+    // rebuilt, outermost first
+    pending: Vec<(Breadcrumbs, String, Value)>,
+}
+
+impl<W: Write> Matches<W> {
+    fn node(&mut self, breadcrumbs: &Breadcrumbs, value: &Value, matched: bool) -> io::Result<()> {
+        // a new document, or a node outside the outermost match, completes it
+        let outside = match self.pending.first() {
+            Some((outermost, _, _)) => breadcrumbs.is_empty() || !is_within(outermost, breadcrumbs),
+            None => false,
+        };
+        if outside {
+            self.flush()?;
+        }
+        // a duplicate key replaces the matches made within its earlier value,
+        // as it does when the document is parsed
+        self.pending
+            .retain(|(pending, _, _)| pending != breadcrumbs && !is_within(breadcrumbs, pending));
+        for (ancestor, _, rebuilt) in &mut self.pending {
+            if is_within(ancestor, breadcrumbs) {
+                let relative = breadcrumbs.clone().split_off(ancestor.len());
+                place(
+                    rebuilt,
+                    &JsonPath::from_breadcrumbs(&relative).0,
+                    value.clone(),
+                )?;
+            }
+        }
+        if matched {
+            let path = JsonPath::from_breadcrumbs(breadcrumbs).to_string();
+            if value.is_array() || value.is_object() || !self.pending.is_empty() {
+                // the match, or one which encloses it, is incomplete
+                self.pending
+                    .push((breadcrumbs.clone(), path, value.clone()));
+            } else {
+                writeln!(self.out, "{}\t{}", path, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    // print the matches pending
+    fn flush(&mut self) -> io::Result<()> {
+        for (_, path, value) in self.pending.drain(..) {
+            writeln!(self.out, "{}\t{}", path, value)?;
+        }
+        Ok(())
+    }
+}
+
+// true if `descendant` is strictly within `ancestor`
+fn is_within(ancestor: &Breadcrumbs, descendant: &Breadcrumbs) -> bool {
+    descendant.len() > ancestor.len() && descendant.iter().zip(ancestor).all(|(d, a)| d == a)
+}
+
+// place `value` at `path` within `root`, whose parent has already been placed
+fn place(root: &mut Value, path: &[Segment], value: Value) -> io::Result<()> {
+    let (last, parents) = match path.split_last() {
+        Some(split) => split,
+        None => return Ok(()),
+    };
+    let unplaced = || {
+        io::Error::new(
+            ErrorKind::InvalidData,
+            "a value was streamed before its parent",
+        )
+    };
+    let mut parent = root;
+    for segment in parents {
+        parent = match (parent, segment) {
+            (Value::Array(items), Segment::Index(idx)) => items.get_mut(*idx),
+            (Value::Object(members), Segment::Key(key)) => members.get_mut(key),
+            _ => None,
+        }
+        .ok_or_else(unplaced)?;
+    }
+    match (parent, last) {
+        (Value::Array(items), Segment::Index(_)) => items.push(value),
+        (Value::Object(members), Segment::Key(key)) => {
+            members.insert(key.clone(), value);
+        }
+        _ => return Err(unplaced()),
+    }
+    Ok(())
+}
+
+fn run(args: Args) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+
+    match args.command {
+        Command::Paths => for_each_node(&args.files, |breadcrumbs, value| {
+            let path = JsonPath::from_breadcrumbs(breadcrumbs);
+            match value {
+                Value::Array(_) | Value::Object(_) => {
+                    writeln!(out, "{}\t{}", path, Kind::of(value))
+                }
+                _ => writeln!(out, "{}\t{}\t{}", path, Kind::of(value), value),
+            }
+        })?,
+        Command::Filter(pattern) => {
+            let mut found = Matches {
+                out: &mut out,
+                pending: Vec::new(),
+            };
+            for_each_node(&args.files, |breadcrumbs, value| {
+                let path = JsonPath::from_breadcrumbs(breadcrumbs).to_string();
+                found.node(breadcrumbs, value, matches(&pattern, &path))
+            })?;
+            found.flush()?;
+        }
+        Command::Summary => {
+            let mut schema = Schema::new();
+            observe_files(&mut schema, &args.files)?;
+            write!(out, "{}", schema)?;
+        }
+        Command::Types { root } => {
+            let mut schema = Schema::with_max_examples(0);
+            observe_files(&mut schema, &args.files)?;
+            write!(out, "{}", Generator::new(&root).generate(&schema))?;
+        }
+    }
+
+    out.flush()
+}
+
+fn observe_files(schema: &mut Schema, files: &[String]) -> io::Result<()> {
+    for_each_file(files, |file, reader| {
+        schema
+            .observe_reader(reader)
+            .map(|_| ())
+            .map_err(|err| located(file, err.into()))
+    })
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("spectacle: {}", err);
+            process::exit(1);
+        }
+    };
+    match run(args) {
+        // the reader of the output has stopped reading, which is not an error
+        Err(err) if err.kind() == ErrorKind::BrokenPipe => {}
+        Err(err) => {
+            eprintln!("spectacle: {}", err);
+            process::exit(1);
+        }
+        Ok(()) => {}
+    }
+}
@@ -0,0 +1,257 @@
+//! Compare two values of the same type, reporting where they differ.
+//!
+//! Both values are introspected into [`Node`] trees, which are walked in
+//! lockstep. The children of two corresponding nodes correspond if they have
+//! the same label: the same field, variant and field, list index, map key or
+//! set member. A child which only one node has is reported as added or
+//! removed, without reporting its descendants. Two corresponding leaves are
+//! compared by value.
+//!
+//! Two corresponding enums are reported as changed if their active variants
+//! differ, such as a unit variant replaced by another, and their children are
+//! then compared as usual. The active variant of an enum is only known from
+//! its [`TypeInfo`], so [`diff_typed`] reports changes of variant, while
+//! [`diff`], which accepts any `Introspect` value, only finds the values
+//! beneath the variants, and so cannot tell unit variants apart.
+//!
+//! A [`Diff`] displays one difference per line, with paths aligned:
+//!
+//! ```text
+//! ~ $.name           "svc" -> "api"
+//! + $.ports[2]       u16 = 8080
+//! - $.mode::Fixed.0  i32 = -1
+//! ```
+
+use crate::{
+    dom::{short_type_name, to_node_with, to_typed_node_with, Node, NodeKind},
+    format_path, Breadcrumbs, Introspect, Options, TypeInfo,
+};
+use std::{collections::HashMap, fmt};
+
+/// How a value differs between the old and new roots.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Change {
+    /// The value is present only in the new root.
+    Added(Node),
+    /// The value is present only in the old root.
+    Removed(Node),
+    /// The value is present in both roots, but is a leaf with a different
+    /// value, or has a different type.
+    Changed { old: Node, new: Node },
+}
+
+/// A single difference between two values.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Difference {
+    /// The path to the differing value, such as `$.foo[0]`.
+    pub path: String,
+    pub change: Change,
+}
+
+/// The differences between two values, in the order they were found.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Diff {
+    pub differences: Vec<Difference>,
+}
+
+impl Diff {
+    /// True if no differences were found.
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+/// Find the differences between `old` and `new`.
+pub fn diff<T: Introspect + ?Sized>(old: &T, new: &T) -> Diff {
+    diff_with(old, new, &Options::default())
+}
+
+/// Find the differences between `old` and `new`, introspecting both according
+/// to `options`.
+pub fn diff_with<T: Introspect + ?Sized>(old: &T, new: &T, options: &Options) -> Diff {
+    diff_trees(to_node_with(old, options), to_node_with(new, options))
+}
+
+/// Find the differences between `old` and `new`, with the type of each value
+/// found through the `TypeInfo` of `T`.
+pub fn diff_typed<T: Introspect + TypeInfo + ?Sized>(old: &T, new: &T) -> Diff {
+    diff_typed_with(old, new, &Options::default())
+}
+
+/// Find the differences between `old` and `new`, introspecting both according
+/// to `options`, with the type of each value found through the `TypeInfo` of
+/// `T`.
+pub fn diff_typed_with<T: Introspect + TypeInfo + ?Sized>(
+    old: &T,
+    new: &T,
+    options: &Options,
+) -> Diff {
+    diff_trees(
+        to_typed_node_with(old, options),
+        to_typed_node_with(new, options),
+    )
+}
+
+fn diff_trees(old: Option<Node>, new: Option<Node>) -> Diff {
+    match (old, new) {
+        (Some(old), Some(new)) => diff_nodes(&old, &new),
+        // a value which visits nothing is treated as absent
+        (old, new) => Diff {
+            differences: old
+                .map(Change::Removed)
+                .into_iter()
+                .chain(new.map(Change::Added))
+                .map(|change| Difference {
+                    path: format_path(&Breadcrumbs::new()),
+                    change,
+                })
+                .collect(),
+        },
+    }
+}
+
+/// Find the differences between two trees of nodes.
+pub fn diff_nodes(old: &Node, new: &Node) -> Diff {
+    let mut diff = Diff::default();
+    compare(old, new, &mut diff.differences);
+    diff
+}
+
+fn compare(old: &Node, new: &Node, differences: &mut Vec<Difference>) {
+    let changed = match (&old.kind, &new.kind) {
+        (NodeKind::Leaf(a), NodeKind::Leaf(b)) => a != b,
+        (NodeKind::Leaf(_), _) | (_, NodeKind::Leaf(_)) => true,
+        _ => old.type_name != new.type_name,
+    };
+    if changed {
+        differences.push(Difference {
+            path: format_path(&new.breadcrumbs),
+            change: Change::Changed {
+                old: old.clone(),
+                new: new.clone(),
+            },
+        });
+        return;
+    }
+    // the children of equal leaves, such as `serde_json::Number`, are equal
+    if let NodeKind::Leaf(_) = old.kind {
+        return;
+    }
+    // a change of variant is reported even where the variants have no fields
+    // to compare, such as unit variants; any fields are compared below
+    if old.variant != new.variant {
+        differences.push(Difference {
+            path: format_path(&new.breadcrumbs),
+            change: Change::Changed {
+                old: old.clone(),
+                new: new.clone(),
+            },
+        });
+    }
+
+    let mut new_children: HashMap<&str, &Node> = new
+        .children
+        .iter()
+        .map(|child| (child.label.as_str(), child))
+        .collect();
+    for child in &old.children {
+        match new_children.remove(child.label.as_str()) {
+            Some(new_child) => compare(child, new_child, differences),
+            None => differences.push(Difference {
+                path: format_path(&child.breadcrumbs),
+                change: Change::Removed(child.clone()),
+            }),
+        }
+    }
+    for child in &new.children {
+        if new_children.contains_key(child.label.as_str()) {
+            differences.push(Difference {
+                path: format_path(&child.breadcrumbs),
+                change: Change::Added(child.clone()),
+            });
+        }
+    }
+}
+
+// the type of a node and its value, if it is a leaf, or its variant, if it is
+// an enum whose variant is known
+fn describe(node: &Node) -> String {
+    let type_name = node
+        .type_name
+        .map(short_type_name)
+        .unwrap_or_else(|| "?".to_string());
+    match (&node.kind, node.variant) {
+        (NodeKind::Leaf(leaf), _) => format!("{} = {}", type_name, leaf),
+        (_, Some(variant)) => format!("{}::{}", type_name, variant),
+        _ => type_name,
+    }
+}
+
+impl Change {
+    fn sign(&self) -> char {
+        match self {
+            Change::Added(_) => '+',
+            Change::Removed(_) => '-',
+            Change::Changed { .. } => '~',
+        }
+    }
+
+    // the value added or removed, or the old and new values
+    fn summary(&self) -> String {
+        match self {
+            Change::Added(node) | Change::Removed(node) => describe(node),
+            Change::Changed { old, new } => match (&old.kind, &new.kind) {
+                (NodeKind::Leaf(a), NodeKind::Leaf(b)) if old.type_name == new.type_name => {
+                    format!("{} -> {}", a, b)
+                }
+                _ => format!("{} -> {}", describe(old), describe(new)),
+            },
+        }
+    }
+}
+
+/// A change displays as `+` followed by the added value, `-` followed by the
+/// removed value, or `~` followed by the old and new values. Leaves of the
+/// same type are given by their values alone.
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.sign(), self.summary())
+    }
+}
+
+/// A difference displays as its change, with the path after the sign.
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.change.sign(),
+            self.path,
+            self.change.summary()
+        )
+    }
+}
+
+/// Each difference displays on its own line, with the changes aligned after
+/// the longest path.
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let width = self
+            .differences
+            .iter()
+            .map(|difference| difference.path.chars().count())
+            .max()
+            .unwrap_or_default();
+        for difference in &self.differences {
+            writeln!(
+                f,
+                "{} {:width$}  {}",
+                difference.change.sign(),
+                difference.path,
+                difference.change.summary(),
+                width = width
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,399 @@
+//! Convert any `Introspect` value into an owned, dynamically typed tree.
+//!
+//! Each value visited becomes a [`Node`], whose children are the values
+//! visited beneath it. A node is labelled by the breadcrumbs leading to it
+//! from its parent, such as `.field`, `[0]` or `::Some`; a single label may
+//! span several breadcrumbs, such as `::Variant.0` for the field of an enum
+//! variant.
+//!
+//! A tree can be built from any `Introspect` value with [`to_node`]. The type
+//! of each node is then recognized from the value itself if it is a
+//! primitive, a string, or a `serde_json` type. Values which also implement
+//! [`TypeInfo`] can be converted with [`to_typed_node`], which finds the type
+//! of each node by following its breadcrumbs through the `TypeInfo` of the
+//! root, falling back to the value itself where that is not possible, such as
+//! within a `serde_json::Value`. Primitives and strings are converted into
+//! [`Leaf`] values.
+//!
+//! A tree is only built if introspection visits its root, which a hand-written
+//! `Introspect` implementation may not. Visits which are not beneath the root
+//! are ignored.
+//!
+//! With the `serde-json` feature, a tree can be exported with
+//! [`Node::to_json`].
+
+use crate::{
+    type_info::{Type, TypeKind},
+    Breadcrumb, Breadcrumbs, Introspect, Options, TypeInfo,
+};
+use std::{any::Any, fmt};
+
+/// A value visited during introspection, and the values visited beneath it.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Node {
+    /// The breadcrumbs leading to this node from its parent, formatted as a
+    /// path. The label of the root is `$`.
+    pub label: String,
+    /// The breadcrumbs leading to this node from the root.
+    pub breadcrumbs: Breadcrumbs,
+    /// The name of the node's type, if it could be determined.
+    pub type_name: Option<&'static str>,
+    /// What sort of value the node is.
+    pub kind: NodeKind,
+    /// The active variant of an enum, if its type names it.
+    pub variant: Option<&'static str>,
+    /// The nodes visited beneath this one, in the order they were visited.
+    pub children: Vec<Node>,
+}
+
+/// What sort of value a [`Node`] is.
+#[derive(Clone, PartialEq, Debug)]
+pub enum NodeKind {
+    /// A primitive or string, with its value.
+    Leaf(Leaf),
+    Struct,
+    Enum,
+    Tuple,
+    /// An array or list.
+    List,
+    Set,
+    Map,
+    Option,
+    Result,
+    /// A `serde_json::Value` or raw JSON.
+    Json,
+    /// A value of unknown structure.
+    Opaque,
+}
+
+/// The value of a primitive or string.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Leaf {
+    Unit,
+    Bool(bool),
+    Char(char),
+    Signed(i128),
+    Unsigned(u128),
+    Float(f64),
+    String(String),
+}
+
+/// Leaves display as Rust literals; strings and chars are quoted as by `Debug`.
+impl fmt::Display for Leaf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Leaf::Unit => f.write_str("()"),
+            Leaf::Bool(b) => write!(f, "{}", b),
+            Leaf::Char(c) => write!(f, "{:?}", c),
+            Leaf::Signed(n) => write!(f, "{}", n),
+            Leaf::Unsigned(n) => write!(f, "{}", n),
+            Leaf::Float(n) => write!(f, "{:?}", n),
+            Leaf::String(s) => write!(f, "{:?}", s),
+        }
+    }
+}
+
+/// Build the tree of values visited by introspecting `value`, or `None` if
+/// nothing was visited.
+pub fn to_node<T: Introspect + ?Sized>(value: &T) -> Option<Node> {
+    to_node_with(value, &Options::default())
+}
+
+/// Build the tree of values visited by introspecting `value` according to
+/// `options`, or `None` if nothing was visited.
+pub fn to_node_with<T: Introspect + ?Sized>(value: &T, options: &Options) -> Option<Node> {
+    build(value, options, None)
+}
+
+/// Build the tree of values visited by introspecting `value`, with the type
+/// of each node found through the `TypeInfo` of `T`.
+pub fn to_typed_node<T: Introspect + TypeInfo + ?Sized>(value: &T) -> Option<Node> {
+    to_typed_node_with(value, &Options::default())
+}
+
+/// Build the tree of values visited by introspecting `value` according to
+/// `options`, with the type of each node found through the `TypeInfo` of `T`.
+pub fn to_typed_node_with<T: Introspect + TypeInfo + ?Sized>(
+    value: &T,
+    options: &Options,
+) -> Option<Node> {
+    build(value, options, Some(T::type_info()))
+}
+
+fn build<T: Introspect + ?Sized>(value: &T, options: &Options, root: Option<Type>) -> Option<Node> {
+    // the nodes being built, each with its type, from the root to the most
+    // recently visited node
+    let mut stack: Vec<(Option<Type>, Node)> = Vec::new();
+    value.introspect_with(options, |breadcrumbs, item| {
+        while let Some((_, parent)) = stack.last() {
+            if is_strict_prefix(&parent.breadcrumbs, breadcrumbs) {
+                break;
+            }
+            if stack.len() == 1 {
+                // not beneath the root
+                return;
+            }
+            pop(&mut stack);
+        }
+
+        let (label, ty) = match stack.last() {
+            None => ("$".to_string(), root.clone()),
+            Some((parent_ty, parent)) => {
+                let steps: Vec<Breadcrumb> = breadcrumbs
+                    .iter()
+                    .skip(parent.breadcrumbs.len())
+                    .cloned()
+                    .collect();
+                let label = steps.iter().map(Breadcrumb::to_string).collect();
+                (label, parent_ty.as_ref().and_then(|ty| resolve(ty, &steps)))
+            }
+        };
+        let ty = ty.or_else(|| known_type(item));
+        let kind = match leaf(item) {
+            Some(leaf) => NodeKind::Leaf(leaf),
+            None => ty.as_ref().map_or(NodeKind::Opaque, |ty| kind_of(&ty.kind)),
+        };
+        let node = Node {
+            label,
+            breadcrumbs: breadcrumbs.clone(),
+            type_name: ty.as_ref().map(|ty| ty.name),
+            kind,
+            variant: ty
+                .as_ref()
+                .and_then(|ty| ty.active_variant)
+                .and_then(|active| active.of(item)),
+            children: Vec::new(),
+        };
+        stack.push((ty, node));
+    });
+
+    while stack.len() > 1 {
+        pop(&mut stack);
+    }
+    stack.pop().map(|(_, node)| node)
+}
+
+// move the last node on the stack into the children of its parent
+fn pop(stack: &mut Vec<(Option<Type>, Node)>) {
+    if stack.len() > 1 {
+        let (_, node) = stack.pop().expect("stack is not empty");
+        stack
+            .last_mut()
+            .expect("stack has a parent")
+            .1
+            .children
+            .push(node);
+    }
+}
+
+pub(crate) fn is_strict_prefix(prefix: &Breadcrumbs, breadcrumbs: &Breadcrumbs) -> bool {
+    prefix.len() < breadcrumbs.len() && prefix.iter().zip(breadcrumbs.iter()).all(|(a, b)| a == b)
+}
+
+// the type of the child of `ty` reached by `steps`
+fn resolve(ty: &Type, steps: &[Breadcrumb]) -> Option<Type> {
+    if let ([Breadcrumb::MapKey(_)], TypeKind::Map(key, _)) = (steps, &ty.kind) {
+        return Some(key.get());
+    }
+    let any = || "*".to_string();
+    let steps: Vec<Breadcrumb> = steps
+        .iter()
+        .map(|step| match step {
+            Breadcrumb::Index(_) => Breadcrumb::Index(any()),
+            Breadcrumb::SetMember(_) => Breadcrumb::SetMember(any()),
+            other => other.clone(),
+        })
+        .collect();
+    ty.children()
+        .into_iter()
+        .find(|(path, _)| *path == steps)
+        .map(|(_, child)| child.get())
+}
+
+fn kind_of(kind: &TypeKind) -> NodeKind {
+    match kind {
+        TypeKind::Struct(_) => NodeKind::Struct,
+        TypeKind::Enum(_) => NodeKind::Enum,
+        TypeKind::Tuple(_) => NodeKind::Tuple,
+        TypeKind::Array(..) | TypeKind::List(_) => NodeKind::List,
+        TypeKind::Set(_) => NodeKind::Set,
+        TypeKind::Map(..) => NodeKind::Map,
+        TypeKind::Option(_) => NodeKind::Option,
+        TypeKind::Result(..) => NodeKind::Result,
+        TypeKind::Json => NodeKind::Json,
+        TypeKind::Primitive(_) | TypeKind::Opaque => NodeKind::Opaque,
+    }
+}
+
+// a type name without the module path of any type within it:
+// `alloc::vec::Vec<my::Item>` becomes `Vec<Item>`
+pub(crate) fn short_type_name(type_name: &str) -> String {
+    let mut out = String::new();
+    let mut word = String::new();
+    let mut chars = type_name.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_alphanumeric() || c == '_' {
+            word.push(c);
+        } else if c == ':' && chars.peek() == Some(&':') {
+            chars.next();
+            word.clear();
+        } else {
+            out.push_str(&word);
+            word.clear();
+            out.push(c);
+        }
+    }
+    out.push_str(&word);
+    out
+}
+
+macro_rules! downcast_leaf {
+    ($item:expr, $($t:ty => $leaf:expr),* $(,)?) => {
+        $(
+            if let Some(&value) = $item.downcast_ref::<$t>() {
+                return Some($leaf(value));
+            }
+        )*
+    };
+}
+
+// the value of a primitive or string
+fn leaf(item: &dyn Any) -> Option<Leaf> {
+    downcast_leaf!(
+        item,
+        () => |()| Leaf::Unit,
+        bool => Leaf::Bool,
+        char => Leaf::Char,
+        u8 => |n| Leaf::Unsigned(u128::from(n)),
+        u16 => |n| Leaf::Unsigned(u128::from(n)),
+        u32 => |n| Leaf::Unsigned(u128::from(n)),
+        u64 => |n| Leaf::Unsigned(u128::from(n)),
+        u128 => Leaf::Unsigned,
+        usize => |n| Leaf::Unsigned(n as u128),
+        i8 => |n| Leaf::Signed(i128::from(n)),
+        i16 => |n| Leaf::Signed(i128::from(n)),
+        i32 => |n| Leaf::Signed(i128::from(n)),
+        i64 => |n| Leaf::Signed(i128::from(n)),
+        i128 => Leaf::Signed,
+        isize => |n| Leaf::Signed(n as i128),
+        f32 => |n| Leaf::Float(f64::from(n)),
+        f64 => Leaf::Float,
+        &'static str => |s: &str| Leaf::String(s.to_string()),
+    );
+    if let Some(s) = item.downcast_ref::<String>() {
+        return Some(Leaf::String(s.clone()));
+    }
+    #[cfg(feature = "serde-json")]
+    if let Some(n) = item.downcast_ref::<serde_json::Number>() {
+        return Some(match (n.as_u64(), n.as_i64()) {
+            (Some(n), _) => Leaf::Unsigned(u128::from(n)),
+            (None, Some(n)) => Leaf::Signed(i128::from(n)),
+            (None, None) => Leaf::Float(n.as_f64().unwrap_or(f64::NAN)),
+        });
+    }
+    None
+}
+
+macro_rules! downcast_type {
+    ($item:expr, $($t:ty),* $(,)?) => {
+        $(
+            if $item.is::<$t>() {
+                return Some(<$t>::type_info());
+            }
+        )*
+    };
+}
+
+// the type of a value which is not known from the types of its ancestors
+pub(crate) fn known_type(item: &dyn Any) -> Option<Type> {
+    downcast_type!(
+        item,
+        (),
+        bool,
+        char,
+        u8,
+        u16,
+        u32,
+        u64,
+        u128,
+        usize,
+        i8,
+        i16,
+        i32,
+        i64,
+        i128,
+        isize,
+        f32,
+        f64,
+        &'static str,
+        String,
+    );
+    #[cfg(feature = "serde-json")]
+    downcast_type!(
+        item,
+        serde_json::Value,
+        serde_json::Map<String, serde_json::Value>,
+        Vec<serde_json::Value>,
+        serde_json::Number,
+        Box<serde_json::value::RawValue>,
+    );
+    None
+}
+
+#[cfg(feature = "serde-json")]
+impl Node {
+    /// Export this tree as JSON.
+    ///
+    /// Each node is an object with its `label`, its `type` (or `null` if
+    /// unknown), and its `kind` in snake case. An enum whose active variant
+    /// is known also has its `variant`, a leaf has its `value`, and a node
+    /// with children has its `children` as an array. Integers
+    /// beyond the range of a JSON number, and non-finite floats, are given as
+    /// strings.
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::{json, Value};
+        use std::convert::TryFrom;
+
+        let kind = match &self.kind {
+            NodeKind::Leaf(_) => "leaf",
+            NodeKind::Struct => "struct",
+            NodeKind::Enum => "enum",
+            NodeKind::Tuple => "tuple",
+            NodeKind::List => "list",
+            NodeKind::Set => "set",
+            NodeKind::Map => "map",
+            NodeKind::Option => "option",
+            NodeKind::Result => "result",
+            NodeKind::Json => "json",
+            NodeKind::Opaque => "opaque",
+        };
+        let mut out = json!({
+            "label": self.label,
+            "type": self.type_name,
+            "kind": kind,
+        });
+        if let Some(variant) = self.variant {
+            out["variant"] = json!(variant);
+        }
+        if let NodeKind::Leaf(leaf) = &self.kind {
+            out["value"] = match leaf {
+                Leaf::Unit => Value::Null,
+                Leaf::Bool(b) => json!(b),
+                Leaf::Char(c) => json!(c),
+                Leaf::Signed(n) => {
+                    i64::try_from(*n).map_or_else(|_| json!(n.to_string()), |n| json!(n))
+                }
+                Leaf::Unsigned(n) => {
+                    u64::try_from(*n).map_or_else(|_| json!(n.to_string()), |n| json!(n))
+                }
+                Leaf::Float(n) => serde_json::Number::from_f64(*n)
+                    .map_or_else(|| json!(n.to_string()), Value::Number),
+                Leaf::String(s) => json!(s),
+            };
+        }
+        if !self.children.is_empty() {
+            out["children"] = self.children.iter().map(Node::to_json).collect();
+        }
+        out
+    }
+}
@@ -0,0 +1,445 @@
+//! Flatten an introspected value into pairs of paths and leaf values, for
+//! logging or CSV export:
+//!
+//! ```text
+//! server.name = "svc"
+//! server.ports[0] = 8080
+//! server.mode.Fixed[0] = -1
+//! ```
+//!
+//! Each leaf visited, whether a primitive, a string or a `serde_json` leaf,
+//! is paired with the path leading to it. The paths and values are written
+//! in the way `serde_json` would represent the value:
+//!
+//! - fields, enum variants and the keys of JSON objects follow a dot, as
+//!   `.name`, unless they are the first segment of the path;
+//! - list, array and tuple items are given by their index in brackets, as
+//!   `[0]`, as are the members of ordered sets;
+//! - map entries are given by the `Debug` representation of their keys in
+//!   brackets, as `["key"]`, as are JSON object keys which are not
+//!   identifiers;
+//! - `Option`s and `serde_json::Value`s add no segment of their own;
+//! - strings and chars are quoted as JSON strings, `()` is `null`, and other
+//!   leaves are written as they display.
+//!
+//! A value with nothing visited beneath it is paired with a value of its own
+//! where its type is known: an empty array, list or set is `[]`, an empty map
+//! is `{}`, and an enum variant without fields is its name as a JSON string,
+//! as `mode = "Off"`. [`flatten`] only knows the types of `serde_json` values,
+//! while [`flatten_typed`] finds the type of every value through the
+//! [`TypeInfo`] of the root. Other values which contain no leaves, such as
+//! `None`, produce no pairs.
+//!
+//! With the `serde-json` feature, [`unflatten`] rebuilds a
+//! `serde_json::Value` from such pairs.
+
+use crate::{
+    dom::{is_strict_prefix, to_node_with, to_typed_node_with, Node, NodeKind},
+    Breadcrumb, Breadcrumbs, Introspect, Options, TypeInfo,
+};
+use std::{any::Any, collections::HashSet, fmt::Write};
+
+/// Flatten `value` into pairs of the path to each leaf and its value.
+pub fn flatten<T: Introspect + ?Sized>(value: &T) -> Vec<(String, String)> {
+    flatten_with(value, &Options::default())
+}
+
+/// Flatten `value` into pairs of the path to each leaf and its value,
+/// introspecting it according to `options`.
+///
+/// Map keys are never flattened, even if `options.map_keys` is set, as they
+/// are already part of the paths of their values.
+pub fn flatten_with<T: Introspect + ?Sized>(value: &T, options: &Options) -> Vec<(String, String)> {
+    flatten_by(value, options, || to_node_with(value, options))
+}
+
+/// Flatten `value` into pairs of the path to each leaf and its value, with
+/// the type of each value found through the `TypeInfo` of `T`.
+pub fn flatten_typed<T: Introspect + TypeInfo + ?Sized>(value: &T) -> Vec<(String, String)> {
+    flatten_typed_with(value, &Options::default())
+}
+
+/// Flatten `value` into pairs of the path to each leaf and its value,
+/// introspecting it according to `options`, with the type of each value found
+/// through the `TypeInfo` of `T`.
+pub fn flatten_typed_with<T: Introspect + TypeInfo + ?Sized>(
+    value: &T,
+    options: &Options,
+) -> Vec<(String, String)> {
+    flatten_by(value, options, || to_typed_node_with(value, options))
+}
+
+// Flatten `value`, finding the types of the values with nothing beneath them
+// in the tree built by `tree`, which is only built if there are any.
+fn flatten_by<T, F>(value: &T, options: &Options, tree: F) -> Vec<(String, String)>
+where
+    T: Introspect + ?Sized,
+    F: FnOnce() -> Option<Node>,
+{
+    // the path of each pair, and its value if it is a leaf
+    let mut pairs: Vec<(String, Option<String>)> = Vec::new();
+    // the breadcrumbs of each pair which is not a leaf
+    let mut empty: Vec<(usize, Breadcrumbs)> = Vec::new();
+    // the paths of the JSON values visited, whose variants add no segment
+    let mut json_values = HashSet::new();
+    // the path of the last leaf, whose descendants are part of its value
+    let mut last_leaf: Option<Breadcrumbs> = None;
+    // the last value visited which is not a leaf, if nothing has been visited
+    // beneath it yet
+    let mut childless: Option<Breadcrumbs> = None;
+    value.introspect_with(options, |breadcrumbs, item| {
+        if let Some(leaf) = &last_leaf {
+            if is_strict_prefix(leaf, breadcrumbs) {
+                return;
+            }
+        }
+        if let Some(parent) = childless.take() {
+            if !is_strict_prefix(&parent, breadcrumbs) {
+                empty.push((pairs.len(), parent.clone()));
+                pairs.push((flat_path(&parent, &json_values), None));
+            }
+        }
+        if breadcrumbs
+            .iter()
+            .any(|breadcrumb| matches!(breadcrumb, Breadcrumb::MapKey(_)))
+        {
+            return;
+        }
+        if is_json_value(item) {
+            json_values.insert(breadcrumbs.clone());
+        }
+        match leaf_text(item) {
+            Some(text) => {
+                pairs.push((flat_path(breadcrumbs, &json_values), Some(text)));
+                last_leaf = Some(breadcrumbs.clone());
+            }
+            None => childless = Some(breadcrumbs.clone()),
+        }
+    });
+    if let Some(parent) = childless {
+        empty.push((pairs.len(), parent.clone()));
+        pairs.push((flat_path(&parent, &json_values), None));
+    }
+
+    if !empty.is_empty() {
+        if let Some(root) = tree() {
+            for (idx, breadcrumbs) in empty {
+                pairs[idx].1 = find(&root, &breadcrumbs).and_then(empty_text);
+            }
+        }
+    }
+    pairs
+        .into_iter()
+        .filter_map(|(path, value)| Some((path, value?)))
+        .collect()
+}
+
+// the node at `breadcrumbs` within `root`
+fn find<'a>(root: &'a Node, breadcrumbs: &Breadcrumbs) -> Option<&'a Node> {
+    let mut node = root;
+    while node.breadcrumbs.len() < breadcrumbs.len() {
+        node = node.children.iter().find(|child| {
+            child.breadcrumbs == *breadcrumbs || is_strict_prefix(&child.breadcrumbs, breadcrumbs)
+        })?;
+    }
+    Some(node).filter(|node| node.breadcrumbs == *breadcrumbs)
+}
+
+// the value of a node with nothing beneath it, as described in the module
+// documentation
+fn empty_text(node: &Node) -> Option<String> {
+    match (&node.kind, node.variant) {
+        (NodeKind::List, _) | (NodeKind::Set, _) => Some("[]".to_string()),
+        (NodeKind::Map, _) => Some("{}".to_string()),
+        (NodeKind::Enum, Some(variant)) => Some(quote(variant)),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "serde-json")]
+fn is_json_value(item: &dyn Any) -> bool {
+    item.is::<serde_json::Value>()
+}
+
+#[cfg(not(feature = "serde-json"))]
+fn is_json_value(_item: &dyn Any) -> bool {
+    false
+}
+
+// the path of a leaf, as described in the module documentation
+fn flat_path(breadcrumbs: &Breadcrumbs, json_values: &HashSet<Breadcrumbs>) -> String {
+    let mut path = String::new();
+    let mut parent = Breadcrumbs::new();
+    // true if the previous breadcrumb was the variant of a JSON object
+    let mut in_object = false;
+    for breadcrumb in breadcrumbs {
+        let from_json = json_values.contains(&parent);
+        parent.push_back(breadcrumb.clone());
+        match breadcrumb {
+            Breadcrumb::Variant(variant) => {
+                in_object = from_json && *variant == "Object";
+                if !from_json && *variant != "Some" {
+                    push_name(&mut path, variant);
+                }
+                continue;
+            }
+            Breadcrumb::Field(field) => push_name(&mut path, field),
+            Breadcrumb::Index(key) if in_object => {
+                if is_identifier(key) {
+                    push_name(&mut path, key);
+                } else {
+                    let _ = write!(path, "[{}]", quote(key));
+                }
+            }
+            Breadcrumb::Index(index) | Breadcrumb::SetMember(index) | Breadcrumb::MapKey(index) => {
+                if index.starts_with('"') || index.bytes().all(|b| b.is_ascii_digit()) {
+                    let _ = write!(path, "[{}]", index);
+                } else {
+                    let _ = write!(path, "[{}]", quote(index));
+                }
+            }
+            Breadcrumb::TupleIndex(idx) => {
+                let _ = write!(path, "[{}]", idx);
+            }
+        }
+        in_object = false;
+    }
+    path
+}
+
+fn push_name(path: &mut String, name: &str) {
+    if !path.is_empty() {
+        path.push('.');
+    }
+    path.push_str(name);
+}
+
+// true if `key` can follow a dot in a path without being quoted
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    matches!(chars.next(), Some(c) if c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+// `s` as a JSON string
+fn quote(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// the value of a leaf, as described in the module documentation
+fn leaf_text(item: &dyn Any) -> Option<String> {
+    macro_rules! display {
+        ($($t:ty),*) => {
+            $(
+                if let Some(value) = item.downcast_ref::<$t>() {
+                    return Some(value.to_string());
+                }
+            )*
+        };
+    }
+
+    display!(bool, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+    if let Some(n) = item.downcast_ref::<f32>() {
+        return Some(format!("{:?}", n));
+    }
+    if let Some(n) = item.downcast_ref::<f64>() {
+        return Some(format!("{:?}", n));
+    }
+    if item.is::<()>() {
+        return Some("null".to_string());
+    }
+    if let Some(c) = item.downcast_ref::<char>() {
+        return Some(quote(c.encode_utf8(&mut [0; 4])));
+    }
+    if let Some(s) = item.downcast_ref::<String>() {
+        return Some(quote(s));
+    }
+    if let Some(s) = item.downcast_ref::<&'static str>() {
+        return Some(quote(s));
+    }
+    #[cfg(feature = "serde-json")]
+    {
+        if let Some(n) = item.downcast_ref::<serde_json::Number>() {
+            return Some(n.to_string());
+        }
+        if let Some(raw) = item.downcast_ref::<Box<serde_json::value::RawValue>>() {
+            return Some(raw.get().to_string());
+        }
+    }
+    None
+}
+
+#[cfg(feature = "serde-json")]
+pub use self::unflatten::{unflatten, UnflattenError};
+
+#[cfg(feature = "serde-json")]
+mod unflatten {
+    use serde_json::{Map, Value};
+    use std::fmt;
+
+    /// A pair which could not be placed within the rebuilt value.
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    pub struct UnflattenError {
+        /// The path of the pair.
+        pub path: String,
+        pub message: String,
+    }
+
+    /// An unflatten error displays as the path and what went wrong:
+    /// `server.ports[0]: expected an object, found an array`.
+    impl fmt::Display for UnflattenError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+
+    impl std::error::Error for UnflattenError {}
+
+    // a segment of a flattened path
+    enum Segment {
+        Key(String),
+        Index(usize),
+    }
+
+    /// Rebuild a `serde_json::Value` from pairs of paths and leaf values, as
+    /// produced by [`flatten`](super::flatten).
+    ///
+    /// Each segment of a path is an object key, unless it is an unquoted index
+    /// in brackets, which is an array index. Arrays are padded with `null`s
+    /// up to each index. Each value is parsed as JSON if it can be, or is
+    /// taken as a string otherwise, as for `NaN`.
+    pub fn unflatten<I, P, V>(pairs: I) -> Result<Value, UnflattenError>
+    where
+        I: IntoIterator<Item = (P, V)>,
+        P: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut root = Value::Null;
+        for (path, value) in pairs {
+            let path = path.as_ref();
+            let error = |message: String| UnflattenError {
+                path: path.to_string(),
+                message,
+            };
+            let segments = parse_path(path).map_err(error)?;
+            let value = value.as_ref();
+            let value =
+                serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()));
+            place(&mut root, &segments, value).map_err(error)?;
+        }
+        Ok(root)
+    }
+
+    fn parse_path(path: &str) -> Result<Vec<Segment>, String> {
+        let mut segments = Vec::new();
+        let mut rest = path;
+        while !rest.is_empty() {
+            if let Some(bracketed) = rest.strip_prefix('[') {
+                let (inner, after) = if bracketed.starts_with('"') {
+                    let len = quoted_len(bracketed).ok_or("unterminated string")?;
+                    (&bracketed[..len], &bracketed[len..])
+                } else {
+                    let end = bracketed.find(']').ok_or("unterminated [")?;
+                    (&bracketed[..end], &bracketed[end..])
+                };
+                rest = after.strip_prefix(']').ok_or("expected ]")?;
+                segments.push(if inner.starts_with('"') {
+                    Segment::Key(serde_json::from_str(inner).map_err(|err| err.to_string())?)
+                } else if let Ok(index) = inner.parse() {
+                    Segment::Index(index)
+                } else {
+                    Segment::Key(inner.to_string())
+                });
+            } else {
+                if !segments.is_empty() {
+                    rest = rest.strip_prefix('.').ok_or("expected . or [")?;
+                }
+                let end = rest.find(['.', '[']).unwrap_or(rest.len());
+                if end == 0 {
+                    return Err("empty segment".to_string());
+                }
+                segments.push(Segment::Key(rest[..end].to_string()));
+                rest = &rest[end..];
+            }
+        }
+        Ok(segments)
+    }
+
+    // the length of the JSON string at the start of `s`, including its quotes
+    fn quoted_len(s: &str) -> Option<usize> {
+        let mut escaped = false;
+        for (idx, c) in s.char_indices().skip(1) {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => return Some(idx + 1),
+                _ => {}
+            }
+        }
+        None
+    }
+
+    // place `value` at `segments` within `node`, creating objects and arrays
+    fn place(node: &mut Value, segments: &[Segment], value: Value) -> Result<(), String> {
+        let (segment, rest) = match segments.split_first() {
+            Some(split) => split,
+            None if node.is_null() => {
+                *node = value;
+                return Ok(());
+            }
+            None => return Err("a value is already present".to_string()),
+        };
+        let child = match segment {
+            Segment::Key(key) => {
+                if node.is_null() {
+                    *node = Value::Object(Map::new());
+                }
+                match node {
+                    Value::Object(map) => map.entry(key.clone()).or_insert(Value::Null),
+                    other => return Err(format!("expected an object, found {}", describe(other))),
+                }
+            }
+            Segment::Index(index) => {
+                if node.is_null() {
+                    *node = Value::Array(Vec::new());
+                }
+                match node {
+                    Value::Array(items) => {
+                        if items.len() <= *index {
+                            items.resize(index + 1, Value::Null);
+                        }
+                        &mut items[*index]
+                    }
+                    other => return Err(format!("expected an array, found {}", describe(other))),
+                }
+            }
+        };
+        place(child, rest, value)
+    }
+
+    fn describe(value: &Value) -> &'static str {
+        match value {
+            Value::Null => "null",
+            Value::Bool(_) => "a boolean",
+            Value::Number(_) => "a number",
+            Value::String(_) => "a string",
+            Value::Array(_) => "an array",
+            Value::Object(_) => "an object",
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! Export an introspected value as a graph, in Graphviz DOT or Mermaid syntax.
+//!
+//! Each value visited is a vertex labelled with its type name, without module
+//! paths, and its value if it is a leaf. The type of a value is shown as `?`
+//! if it is not known: [`to_dot`] and [`to_mermaid`] accept any value, but
+//! only know the types of primitives, strings and `serde_json` values, while
+//! [`to_dot_typed`] and [`to_mermaid_typed`] find the type of every value
+//! through the [`TypeInfo`] of the root. Each edge leads from a value to its
+//! child, labelled with the breadcrumbs between them, such as `.field` or
+//! `[0]`.
+//!
+//! Long collections can be collapsed: beyond `GraphOptions::max_items`, the
+//! remaining items of an array, list, set or map are replaced by a single
+//! dashed vertex labelled `… N more`.
+
+use crate::{
+    dom::{short_type_name, to_node_with, to_typed_node_with, Node, NodeKind},
+    Introspect, Options, TypeInfo,
+};
+use std::fmt::Write;
+
+/// Options controlling how a graph is exported.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct GraphOptions {
+    /// The number of items of each array, list, set or map which are shown.
+    /// Collections are only recognized where their types are known.
+    pub max_items: Option<usize>,
+
+    /// The options with which the value is introspected.
+    pub introspection: Options,
+}
+
+/// Export the values visited by introspecting `value` as a Graphviz DOT digraph.
+///
+/// The graph is empty if nothing was visited.
+pub fn to_dot<T: Introspect + ?Sized>(value: &T, options: &GraphOptions) -> String {
+    dot(&Graph::of(
+        to_node_with(value, &options.introspection),
+        options,
+    ))
+}
+
+/// Export the values visited by introspecting `value` as a Graphviz DOT
+/// digraph, with the type of each value found through the `TypeInfo` of `T`.
+pub fn to_dot_typed<T: Introspect + TypeInfo + ?Sized>(
+    value: &T,
+    options: &GraphOptions,
+) -> String {
+    dot(&Graph::of(
+        to_typed_node_with(value, &options.introspection),
+        options,
+    ))
+}
+
+/// Export the values visited by introspecting `value` as a Mermaid flowchart.
+///
+/// The flowchart is empty if nothing was visited.
+pub fn to_mermaid<T: Introspect + ?Sized>(value: &T, options: &GraphOptions) -> String {
+    mermaid(&Graph::of(
+        to_node_with(value, &options.introspection),
+        options,
+    ))
+}
+
+/// Export the values visited by introspecting `value` as a Mermaid flowchart,
+/// with the type of each value found through the `TypeInfo` of `T`.
+pub fn to_mermaid_typed<T: Introspect + TypeInfo + ?Sized>(
+    value: &T,
+    options: &GraphOptions,
+) -> String {
+    mermaid(&Graph::of(
+        to_typed_node_with(value, &options.introspection),
+        options,
+    ))
+}
+
+/// Export a tree of nodes as a Graphviz DOT digraph.
+pub fn node_to_dot(node: &Node, options: &GraphOptions) -> String {
+    dot(&Graph::new(node, options))
+}
+
+/// Export a tree of nodes as a Mermaid flowchart.
+pub fn node_to_mermaid(node: &Node, options: &GraphOptions) -> String {
+    mermaid(&Graph::new(node, options))
+}
+
+fn dot(graph: &Graph) -> String {
+    let mut out = String::from("digraph {\n    node [shape=box];\n");
+    for vertex in &graph.vertices {
+        let style = if vertex.elided { ", style=dashed" } else { "" };
+        let _ = writeln!(
+            out,
+            "    n{} [label={}{}];",
+            vertex.id,
+            dot_string(&vertex.text),
+            style
+        );
+    }
+    for edge in &graph.edges {
+        let label = match &edge.label {
+            Some(label) => format!(" [label={}]", dot_string(label)),
+            None => String::new(),
+        };
+        let _ = writeln!(out, "    n{} -> n{}{};", edge.from, edge.to, label);
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn mermaid(graph: &Graph) -> String {
+    let mut out = String::from("flowchart TD\n");
+    for vertex in &graph.vertices {
+        let _ = writeln!(
+            out,
+            "    n{}[\"{}\"]",
+            vertex.id,
+            mermaid_text(&vertex.text)
+        );
+        if vertex.elided {
+            let _ = writeln!(out, "    style n{} stroke-dasharray: 5 5", vertex.id);
+        }
+    }
+    for edge in &graph.edges {
+        match &edge.label {
+            Some(label) => {
+                let _ = writeln!(
+                    out,
+                    "    n{} -->|\"{}\"| n{}",
+                    edge.from,
+                    mermaid_text(label),
+                    edge.to
+                );
+            }
+            None => {
+                let _ = writeln!(out, "    n{} -.-> n{}", edge.from, edge.to);
+            }
+        }
+    }
+    out
+}
+
+struct Vertex {
+    id: usize,
+    text: String,
+    // true if this stands in for collapsed collection items
+    elided: bool,
+}
+
+struct Edge {
+    from: usize,
+    to: usize,
+    label: Option<String>,
+}
+
+// The vertices and edges of a tree, numbered in the order visited.
+#[derive(Default)]
+struct Graph {
+    vertices: Vec<Vertex>,
+    edges: Vec<Edge>,
+}
+
+impl Graph {
+    // the graph of a tree, which is empty if there is none
+    fn of(node: Option<Node>, options: &GraphOptions) -> Graph {
+        node.map_or_else(Graph::default, |node| Graph::new(&node, options))
+    }
+
+    fn new(node: &Node, options: &GraphOptions) -> Graph {
+        let mut graph = Graph {
+            vertices: Vec::new(),
+            edges: Vec::new(),
+        };
+        graph.add(node, options);
+        graph
+    }
+
+    // add `node` and its descendants
+    fn add(&mut self, node: &Node, options: &GraphOptions) {
+        let id = self.vertices.len();
+        let mut text = node
+            .type_name
+            .map(short_type_name)
+            .unwrap_or_else(|| "?".to_string());
+        if let NodeKind::Leaf(leaf) = &node.kind {
+            let _ = write!(text, " = {}", leaf);
+        }
+        self.vertices.push(Vertex {
+            id,
+            text,
+            elided: false,
+        });
+
+        let mut shown = node.children.len();
+        if matches!(node.kind, NodeKind::List | NodeKind::Set | NodeKind::Map) {
+            shown = shown.min(options.max_items.unwrap_or(usize::MAX));
+        }
+        for child in &node.children[..shown] {
+            self.edges.push(Edge {
+                from: id,
+                to: self.vertices.len(),
+                label: Some(child.label.clone()),
+            });
+            self.add(child, options);
+        }
+        let elided = node.children.len() - shown;
+        if elided > 0 {
+            let to = self.vertices.len();
+            self.vertices.push(Vertex {
+                id: to,
+                text: format!("… {} more", elided),
+                elided: true,
+            });
+            self.edges.push(Edge {
+                from: id,
+                to,
+                label: None,
+            });
+        }
+    }
+}
+
+// a quoted DOT string
+fn dot_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// text within a quoted Mermaid label, with markup characters as entity codes
+fn mermaid_text(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("#quot;"),
+            '<' => out.push_str("#lt;"),
+            '>' => out.push_str("#gt;"),
+            '#' => out.push_str("#35;"),
+            '\n' => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,433 @@
+//! Generate Rust type definitions from an inferred [`Schema`].
+//!
+//! Every object becomes a struct, except those which appear to use their keys
+//! as data, which become a `HashMap<String, _>`. Values of mixed kinds become
+//! untagged enums. Members which were sometimes missing or `null` become
+//! `Option`s, and keys which are not valid Rust identifiers are renamed.
+//! Numbers are `i64` or `f64` unless the values observed require otherwise,
+//! or [`Generator::narrow_numbers`] is set.
+//! Strings which were all of a single [`Format`](super::format::Format) are
+//! annotated with a suggested type, which replaces `String` if
+//! [`Generator::format_types`] is set. Nothing which was never observed can be
+//! typed; such values are left as `serde_json::Value`, ready to be found by
+//! further introspection.
+
+use super::infer::{Kind, Schema, Shape};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// The derives which are applied to every generated type by default.
+pub const DEFAULT_DERIVES: &[&str] = &["Debug", "Clone", "Deserialize", "Serialize", "Spectacle"];
+
+/// Generate Rust source for the types of `schema`, with the root type named `root`.
+pub fn generate(schema: &Schema, root: &str) -> String {
+    Generator::new(root).generate(schema)
+}
+
+/// Configurable generator of Rust types from a [`Schema`].
+#[derive(Clone, Debug)]
+pub struct Generator {
+    root: String,
+    derives: Vec<String>,
+    max_struct_fields: usize,
+    format_types: bool,
+    narrow_numbers: bool,
+}
+
+impl Generator {
+    /// Create a generator with default settings, whose root type is named `root`.
+    pub fn new(root: &str) -> Generator {
+        Generator {
+            root: root.to_string(),
+            derives: DEFAULT_DERIVES.iter().map(|d| d.to_string()).collect(),
+            max_struct_fields: 64,
+            format_types: false,
+            narrow_numbers: false,
+        }
+    }
+
+    /// Set the derives applied to every generated type.
+    ///
+    /// The `use` declarations at the top of the output are adjusted to suit.
+    pub fn derives<I, S>(mut self, derives: I) -> Generator
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.derives = derives.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the number of distinct keys beyond which an object is considered
+    /// to be a map instead of a struct.
+    pub fn max_struct_fields(mut self, max_struct_fields: usize) -> Generator {
+        self.max_struct_fields = max_struct_fields;
+        self
+    }
+
+    /// Set whether strings which were all of a single format are given the
+    /// Rust type suggested by that format, instead of `String`.
+    ///
+    /// The generated code then depends on the crates which provide those types.
+    /// As those types do not implement `Introspect`, `Spectacle` is not derived
+    /// even if it is one of the [`derives`](Generator::derives).
+    pub fn format_types(mut self, format_types: bool) -> Generator {
+        self.format_types = format_types;
+        self
+    }
+
+    /// Set whether numbers are given the narrowest Rust type which represents
+    /// every value observed, such as `u8`, instead of `i64` or `f64`.
+    pub fn narrow_numbers(mut self, narrow_numbers: bool) -> Generator {
+        self.narrow_numbers = narrow_numbers;
+        self
+    }
+
+    /// Generate Rust source for the types of `schema`.
+    pub fn generate(&self, schema: &Schema) -> String {
+        let mut context = Context {
+            generator: self,
+            // reserve the root's name, so that no other type is given it
+            names: std::iter::once(self.root.clone()).collect(),
+            definitions: Vec::new(),
+            uses_map: false,
+            root_pending: true,
+        };
+        let root = context.type_of(schema.root(), &self.root);
+        if root != self.root {
+            // the root is not a named struct or enum; give it a name anyway
+            context
+                .definitions
+                .insert(0, format!("pub type {} = {};\n", self.root, root));
+        }
+
+        let mut out = String::new();
+        if context.uses_map {
+            out.push_str("use std::collections::HashMap;\n");
+        }
+        if self.is_serde() {
+            let serde: Vec<_> = ["Deserialize", "Serialize"]
+                .iter()
+                .filter(|d| self.derives.iter().any(|derive| derive == *d))
+                .copied()
+                .collect();
+            match serde.as_slice() {
+                [derive] => writeln!(out, "use serde::{};", derive).unwrap(),
+                _ => writeln!(out, "use serde::{{{}}};", serde.join(", ")).unwrap(),
+            }
+        }
+        if self.has_derive("Spectacle") {
+            out.push_str("use spectacle::Spectacle;\n");
+        }
+        for definition in context.definitions {
+            out.push('\n');
+            out.push_str(&definition);
+        }
+        out
+    }
+
+    // the derives applied, which exclude `Spectacle` if format types are used
+    fn applied_derives(&self) -> impl Iterator<Item = &str> {
+        self.derives
+            .iter()
+            .map(String::as_str)
+            .filter(move |&d| !(self.format_types && d == "Spectacle"))
+    }
+
+    fn has_derive(&self, derive: &str) -> bool {
+        self.applied_derives().any(|d| d == derive)
+    }
+
+    fn derive_attribute(&self) -> String {
+        let derives: Vec<_> = self.applied_derives().collect();
+        if derives.is_empty() {
+            String::new()
+        } else {
+            format!("#[derive({})]\n", derives.join(", "))
+        }
+    }
+
+    fn is_serde(&self) -> bool {
+        self.has_derive("Deserialize") || self.has_derive("Serialize")
+    }
+}
+
+struct Context<'a> {
+    generator: &'a Generator,
+    names: HashSet<String>,
+    definitions: Vec<String>,
+    uses_map: bool,
+    // true until the first named type is defined, which is the root if its
+    // hint is the root's name
+    root_pending: bool,
+}
+
+impl<'a> Context<'a> {
+    // the rust type of the values described by `shape`, defining any new types required
+    fn type_of(&mut self, shape: &Shape, hint: &str) -> String {
+        let kinds: Vec<Kind> = Kind::ALL
+            .iter()
+            .copied()
+            .filter(|&kind| kind != Kind::Null && shape.kind_count(kind) > 0)
+            .collect();
+        let base = match kinds.as_slice() {
+            [] => return "serde_json::Value".to_string(),
+            [kind] => self.kind_type(shape, *kind, hint),
+            [Kind::Integer, Kind::Float] => self.kind_type(shape, Kind::Float, hint),
+            _ => self.enum_type(shape, &kinds, hint),
+        };
+        if shape.kind_count(Kind::Null) > 0 {
+            format!("Option<{}>", base)
+        } else {
+            base
+        }
+    }
+
+    fn kind_type(&mut self, shape: &Shape, kind: Kind, hint: &str) -> String {
+        match kind {
+            Kind::Null => "serde_json::Value".to_string(),
+            Kind::Bool => "bool".to_string(),
+            Kind::Integer | Kind::Float => self.number_type(shape, kind),
+            Kind::String => match shape.format().and_then(|format| format.rust_type) {
+                Some(rust_type) if self.generator.format_types => rust_type.to_string(),
+                _ => "String".to_string(),
+            },
+            Kind::Array => match &shape.items {
+                Some(items) => format!("Vec<{}>", self.type_of(items, &format!("{} item", hint))),
+                None => "Vec<serde_json::Value>".to_string(),
+            },
+            Kind::Object if self.is_map(shape) => {
+                self.uses_map = true;
+                let mut values = Shape::default();
+                for field in shape.fields.values() {
+                    values.merge(field, 0);
+                }
+                format!(
+                    "HashMap<String, {}>",
+                    self.type_of(&values, &format!("{} value", hint))
+                )
+            }
+            Kind::Object => self.struct_type(shape, hint),
+        }
+    }
+
+    fn number_type(&self, shape: &Shape, kind: Kind) -> String {
+        let narrowest = match kind {
+            Kind::Integer => shape.numbers.narrowest_integer_type(),
+            _ => shape.numbers.narrowest_type(),
+        };
+        match narrowest {
+            Some(ty) if self.generator.narrow_numbers => ty,
+            // the observed values do not fit in an `i64`
+            Some(ty @ "u64") | Some(ty @ "i128") => ty,
+            _ if kind == Kind::Integer => "i64",
+            _ => "f64",
+        }
+        .to_string()
+    }
+
+    fn struct_type(&mut self, shape: &Shape, hint: &str) -> String {
+        let name = self.type_name(hint);
+        // reserve the definition's position before defining the fields' types
+        let position = self.definitions.len();
+        let mut fields = String::new();
+        let mut field_names = HashSet::new();
+        for (key, field) in &shape.fields {
+            let mut ty = self.type_of(field, key);
+            let mut attributes = Vec::new();
+            let field_name = unique(&mut field_names, field_ident(key));
+            if field_name.trim_start_matches("r#") != key {
+                attributes.push(format!("rename = {:?}", key));
+            }
+            if !shape.is_required(key) {
+                if !ty.starts_with("Option<") {
+                    ty = format!("Option<{}>", ty);
+                }
+                if self.generator.has_derive("Deserialize") {
+                    attributes.push("default".to_string());
+                }
+                if self.generator.has_derive("Serialize") {
+                    attributes.push("skip_serializing_if = \"Option::is_none\"".to_string());
+                }
+            }
+            if let Some(format) = field.format().filter(|_| !self.generator.format_types) {
+                match format.rust_type {
+                    Some(rust_type) => {
+                        writeln!(
+                            fields,
+                            "    /// Format: {}; consider `{}`.",
+                            format, rust_type
+                        )
+                    }
+                    None => writeln!(fields, "    /// Format: {}.", format),
+                }
+                .unwrap();
+            }
+            if field.numbers.loses_precision() {
+                writeln!(
+                    fields,
+                    "    /// Some integers observed cannot be represented exactly as floats."
+                )
+                .unwrap();
+            }
+            if !attributes.is_empty() && self.generator.is_serde() {
+                writeln!(fields, "    #[serde({})]", attributes.join(", ")).unwrap();
+            }
+            writeln!(fields, "    pub {}: {},", field_name, ty).unwrap();
+        }
+
+        self.definitions.insert(
+            position,
+            format!(
+                "{}pub struct {} {{\n{}}}\n",
+                self.generator.derive_attribute(),
+                name,
+                fields
+            ),
+        );
+        name
+    }
+
+    fn enum_type(&mut self, shape: &Shape, kinds: &[Kind], hint: &str) -> String {
+        let name = self.type_name(hint);
+        // reserve the definition's position before defining the variants' types
+        let position = self.definitions.len();
+        let mut variants = String::new();
+        for &kind in kinds {
+            let variant = pascal_case(kind.name());
+            let ty = self.kind_type(shape, kind, &format!("{} {}", hint, kind.name()));
+            writeln!(variants, "    {}({}),", variant, ty).unwrap();
+        }
+        let untagged = if self.generator.is_serde() {
+            "#[serde(untagged)]\n"
+        } else {
+            ""
+        };
+        self.definitions.insert(
+            position,
+            format!(
+                "{}{}pub enum {} {{\n{}}}\n",
+                self.generator.derive_attribute(),
+                untagged,
+                name,
+                variants
+            ),
+        );
+        name
+    }
+
+    // heuristically determine whether an object's keys are data rather than field names
+    fn is_map(&self, shape: &Shape) -> bool {
+        let objects = shape.kind_count(Kind::Object);
+        let fields = shape.fields.len();
+        if fields == 0 {
+            return false;
+        }
+        if fields > self.generator.max_struct_fields {
+            return true;
+        }
+        if shape
+            .fields
+            .keys()
+            .all(|key| key.starts_with(|c: char| c.is_ascii_digit()))
+        {
+            return true;
+        }
+        // many keys, none of which appear in even half of the objects
+        objects > 1 && fields >= 8 && shape.fields.values().all(|field| field.count * 2 < objects)
+    }
+
+    fn type_name(&mut self, hint: &str) -> String {
+        if std::mem::replace(&mut self.root_pending, false) && hint == self.generator.root {
+            return hint.to_string();
+        }
+        let name = match pascal_case(hint) {
+            name if name.is_empty() => "Type".to_string(),
+            name if name.starts_with(|c: char| c.is_ascii_digit()) => format!("T{}", name),
+            name => name,
+        };
+        unique(&mut self.names, name)
+    }
+}
+
+fn unique(names: &mut HashSet<String>, name: String) -> String {
+    let mut candidate = name.clone();
+    let mut n = 2;
+    while names.contains(&candidate) {
+        candidate = format!("{}{}", name, n);
+        n += 1;
+    }
+    names.insert(candidate.clone());
+    candidate
+}
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static",
+    "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual",
+    "where", "while", "yield",
+];
+
+// a snake case identifier for a field
+fn field_ident(key: &str) -> String {
+    let ident = snake_case(key);
+    match ident.as_str() {
+        "" => "field".to_string(),
+        "self" | "super" | "crate" | "_" => format!("{}_", ident),
+        s if s.starts_with(|c: char| c.is_ascii_digit()) => format!("_{}", ident),
+        s if KEYWORDS.contains(&s) => format!("r#{}", ident),
+        _ => ident,
+    }
+}
+
+// split an arbitrary string into words at separators and case changes
+fn words(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let chars: Vec<char> = s.chars().collect();
+    for (idx, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !word.is_empty() {
+            let prev = chars[idx - 1];
+            let next_lower = chars.get(idx + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_lower) {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn snake_case(s: &str) -> String {
+    words(s)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+fn pascal_case(s: &str) -> String {
+    words(s)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first
+                    .to_uppercase()
+                    .chain(chars.flat_map(char::to_lowercase))
+                    .collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
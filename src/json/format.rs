@@ -0,0 +1,251 @@
+//! Classify strings by their format, such as dates, UUIDs or URLs.
+//!
+//! JSON has no types for most kinds of data, so they are encoded as strings.
+//! A [`Format`] recognizes one such encoding. During inference, each string
+//! observed is classified by the first of the schema's formats which matches
+//! it, and every [`Shape`](super::infer::Shape) keeps a histogram of the
+//! formats of its strings.
+//!
+//! The formats are pluggable: any function from `&str` to `bool` can be made
+//! into a format, and [`Schema::formats`](super::infer::Schema::formats)
+//! replaces the [`DEFAULT_FORMATS`] used by inference.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// A named class of strings.
+///
+/// Formats are identified by name alone: two formats with the same name are equal.
+#[derive(Clone, Copy)]
+pub struct Format {
+    /// The name of this format. Where one exists, this is the name used by
+    /// the `format` keyword of JSON Schema.
+    pub name: &'static str,
+    /// The Rust type which best represents strings of this format, if any.
+    pub rust_type: Option<&'static str>,
+    /// Determine whether a string is of this format.
+    pub matches: fn(&str) -> bool,
+}
+
+impl Format {
+    /// Create a new format.
+    pub const fn new(
+        name: &'static str,
+        rust_type: Option<&'static str>,
+        matches: fn(&str) -> bool,
+    ) -> Format {
+        Format {
+            name,
+            rust_type,
+            matches,
+        }
+    }
+}
+
+impl PartialEq for Format {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for Format {}
+
+impl PartialOrd for Format {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Format {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.name.cmp(other.name)
+    }
+}
+
+impl Hash for Format {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+    }
+}
+
+impl fmt::Debug for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Format")
+            .field("name", &self.name)
+            .field("rust_type", &self.rust_type)
+            .finish()
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+/// An RFC 3339 timestamp, such as `2020-01-31T12:00:00Z`.
+pub const DATE_TIME: Format = Format::new(
+    "date-time",
+    Some("chrono::DateTime<chrono::FixedOffset>"),
+    is_date_time,
+);
+/// An RFC 3339 full date, such as `2020-01-31`.
+pub const DATE: Format = Format::new("date", Some("chrono::NaiveDate"), is_date);
+/// A hyphenated UUID, such as `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+pub const UUID: Format = Format::new("uuid", Some("uuid::Uuid"), is_uuid);
+/// An integer written as a string, such as `"-12"`.
+pub const INTEGER: Format = Format::new("integer", None, is_integer);
+/// A decimal number written as a string, such as `"1.50"`.
+pub const DECIMAL: Format = Format::new("decimal", None, is_decimal);
+/// An absolute URL, such as `https://example.com/`.
+pub const URI: Format = Format::new("uri", Some("url::Url"), is_uri);
+/// An email address, such as `someone@example.com`.
+pub const EMAIL: Format = Format::new("email", None, is_email);
+/// Standard base64, padded, of at least eight characters.
+pub const BASE64: Format = Format::new("base64", None, is_base64);
+
+/// The formats used by inference unless others are chosen, in order of precedence.
+pub const DEFAULT_FORMATS: &[Format] =
+    &[DATE_TIME, DATE, UUID, INTEGER, DECIMAL, URI, EMAIL, BASE64];
+
+/// The first of `formats` which matches `s`.
+pub fn classify<'a>(formats: &'a [Format], s: &str) -> Option<&'a Format> {
+    formats.iter().find(|format| (format.matches)(s))
+}
+
+// parse a run of exactly `n` ascii digits from the start of `s`
+fn digits(s: &str, n: usize) -> Option<(u32, &str)> {
+    if s.len() < n || !s.as_bytes()[..n].iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    Some((s[..n].parse().ok()?, &s[n..]))
+}
+
+fn date_prefix(s: &str) -> Option<&str> {
+    let (year, s) = digits(s, 4)?;
+    let s = s.strip_prefix('-')?;
+    let (month, s) = digits(s, 2)?;
+    let s = s.strip_prefix('-')?;
+    let (day, s) = digits(s, 2)?;
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return None,
+    };
+    if day == 0 || day > days {
+        return None;
+    }
+    Some(s)
+}
+
+fn time_prefix(s: &str) -> Option<&str> {
+    let (hour, s) = digits(s, 2)?;
+    let s = s.strip_prefix(':')?;
+    let (minute, s) = digits(s, 2)?;
+    if hour > 23 || minute > 59 {
+        return None;
+    }
+    Some(s)
+}
+
+fn is_date(s: &str) -> bool {
+    date_prefix(s) == Some("")
+}
+
+fn is_date_time(s: &str) -> bool {
+    let time = match date_prefix(s).and_then(|s| s.strip_prefix(&['T', 't'][..])) {
+        Some(time) => time,
+        None => return false,
+    };
+    let rest = match time_prefix(time).and_then(|s| s.strip_prefix(':')) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    // leap seconds are permitted
+    let mut rest = match digits(rest, 2) {
+        Some((second, rest)) if second <= 60 => rest,
+        _ => return false,
+    };
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let end = fraction
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(fraction.len());
+        if end == 0 {
+            return false;
+        }
+        rest = &fraction[end..];
+    }
+    match rest {
+        "Z" | "z" => true,
+        _ => rest
+            .strip_prefix(&['+', '-'][..])
+            .and_then(time_prefix)
+            .is_some_and(str::is_empty),
+    }
+}
+
+fn is_uuid(s: &str) -> bool {
+    let groups: Vec<&str> = s.split('-').collect();
+    let hex = |group: &str| group.bytes().all(|b| b.is_ascii_hexdigit());
+    groups.len() == 5
+        && groups
+            .iter()
+            .zip(&[8, 4, 4, 4, 12])
+            .all(|(group, &len)| group.len() == len && hex(group))
+}
+
+fn is_integer(s: &str) -> bool {
+    let unsigned = s.strip_prefix('-').unwrap_or(s);
+    !unsigned.is_empty() && unsigned.chars().all(|c| c.is_ascii_digit())
+}
+
+fn is_decimal(s: &str) -> bool {
+    s.contains(|c: char| c.is_ascii_digit())
+        && s.contains(&['.', 'e', 'E'][..])
+        && s.chars().all(|c| c.is_ascii_digit() || "+-.eE".contains(c))
+        && s.parse::<f64>().is_ok()
+}
+
+fn is_uri(s: &str) -> bool {
+    let (scheme, rest) = match s.split_once("://") {
+        Some(parts) => parts,
+        None => return false,
+    };
+    scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+        && !rest.is_empty()
+        && !rest.contains(char::is_whitespace)
+}
+
+fn is_email(s: &str) -> bool {
+    let (local, domain) = match s.split_once('@') {
+        Some(parts) => parts,
+        None => return false,
+    };
+    !local.is_empty()
+        && !s.contains(char::is_whitespace)
+        && !domain.contains('@')
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+}
+
+// `usize::is_multiple_of` is newer than the crate's other requirements
+#[allow(unknown_lints, clippy::manual_is_multiple_of)]
+fn is_base64(s: &str) -> bool {
+    let data = s.trim_end_matches('=');
+    s.len() >= 8
+        && s.len() % 4 == 0
+        && s.len() - data.len() <= 2
+        && data
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/')
+        // plain words are far more likely than base64 without digits or symbols
+        && (data.len() < s.len() || !data.chars().all(|c| c.is_ascii_alphabetic()))
+}
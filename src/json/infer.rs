@@ -0,0 +1,379 @@
+//! Infer the shape of underspecified JSON from a corpus of example documents.
+//!
+//! Each document is introspected, and every `serde_json::Value` within it is
+//! recorded at its normalized [`JsonPath`]: all items of an array share the
+//! path `[*]`. Recording a value counts its [`Kind`] and keeps a few distinct
+//! examples of it; strings are also classified by their [`Format`], and
+//! numbers are aggregated into [`Numbers`]. The result is a tree of
+//! [`Shape`]s mirroring the structure of the documents, in which each object
+//! member and array item shape aggregates every value ever found there.
+
+use super::format::{classify, Format, DEFAULT_FORMATS};
+use super::number::Numbers;
+use super::{JsonPath, Segment};
+use crate::{Breadcrumbs, Introspect, Options};
+use serde_json::Value;
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::{fmt, io};
+
+/// How many distinct examples a [`Schema`] keeps at each path by default.
+pub const DEFAULT_MAX_EXAMPLES: usize = 3;
+
+/// The kind of a JSON value.
+///
+/// Unlike `serde_json::Value`, this distinguishes integers from floats.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Kind {
+    Null,
+    Bool,
+    Integer,
+    Float,
+    String,
+    Array,
+    Object,
+}
+
+impl Kind {
+    /// Every kind, in order.
+    pub const ALL: [Kind; 7] = [
+        Kind::Null,
+        Kind::Bool,
+        Kind::Integer,
+        Kind::Float,
+        Kind::String,
+        Kind::Array,
+        Kind::Object,
+    ];
+
+    /// Determine the kind of a value.
+    pub fn of(value: &Value) -> Kind {
+        match value {
+            Value::Null => Kind::Null,
+            Value::Bool(_) => Kind::Bool,
+            Value::Number(n) if n.is_f64() => Kind::Float,
+            Value::Number(_) => Kind::Integer,
+            Value::String(_) => Kind::String,
+            Value::Array(_) => Kind::Array,
+            Value::Object(_) => Kind::Object,
+        }
+    }
+
+    /// The lowercase name of this kind.
+    pub fn name(self) -> &'static str {
+        match self {
+            Kind::Null => "null",
+            Kind::Bool => "bool",
+            Kind::Integer => "integer",
+            Kind::Float => "float",
+            Kind::String => "string",
+            Kind::Array => "array",
+            Kind::Object => "object",
+        }
+    }
+
+    /// True for kinds which have no children.
+    pub fn is_scalar(self) -> bool {
+        !matches!(self, Kind::Array | Kind::Object)
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Aggregated observations of every value found at a particular path.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Shape {
+    /// How many values were observed at this path.
+    pub count: usize,
+    /// How many of the observed values were of each kind.
+    pub kinds: BTreeMap<Kind, usize>,
+    /// Distinct examples of the scalar values observed at this path.
+    pub examples: Vec<Value>,
+    /// How many of the strings observed at this path were of each format.
+    pub formats: BTreeMap<Format, usize>,
+    /// The range and precision of the numbers observed at this path.
+    pub numbers: Numbers,
+    /// The shapes of the members of objects observed at this path.
+    ///
+    /// The `count` of each member is the number of objects in which it was present.
+    pub fields: BTreeMap<String, Shape>,
+    /// The shape of the items of arrays observed at this path.
+    pub items: Option<Box<Shape>>,
+}
+
+impl Shape {
+    /// How many of the observed values were of `kind`.
+    pub fn kind_count(&self, kind: Kind) -> usize {
+        self.kinds.get(&kind).copied().unwrap_or_default()
+    }
+
+    /// True if at least one value was observed, and all values were of `kind`.
+    pub fn is_only(&self, kind: Kind) -> bool {
+        self.count > 0 && self.kind_count(kind) == self.count
+    }
+
+    /// True if the member `key` was present in every object observed at this path.
+    pub fn is_required(&self, key: &str) -> bool {
+        self.fields
+            .get(key)
+            .map(|field| field.count == self.kind_count(Kind::Object))
+            .unwrap_or_default()
+    }
+
+    /// The format of every string observed at this path, if they all share one.
+    pub fn format(&self) -> Option<Format> {
+        let strings = self.kind_count(Kind::String);
+        match self.formats.iter().next() {
+            Some((format, &count)) if strings > 0 && count == strings => Some(*format),
+            _ => None,
+        }
+    }
+
+    /// Look up the shape at a path relative to this one.
+    ///
+    /// Both `Segment::Index` and `Segment::AnyIndex` select the array items.
+    pub fn get(&self, path: &JsonPath) -> Option<&Shape> {
+        let mut shape = self;
+        for segment in &path.0 {
+            shape = match segment {
+                Segment::Key(key) => shape.fields.get(key)?,
+                Segment::Index(_) | Segment::AnyIndex => shape.items.as_deref()?,
+            };
+        }
+        Some(shape)
+    }
+
+    /// Every shape in this tree, paired with its path relative to this one.
+    ///
+    /// Parents precede their children; object members precede array items.
+    pub fn paths(&self) -> Vec<(JsonPath, &Shape)> {
+        let mut paths = Vec::new();
+        self.collect_paths(JsonPath::root(), &mut paths);
+        paths
+    }
+
+    fn collect_paths<'a>(&'a self, path: JsonPath, paths: &mut Vec<(JsonPath, &'a Shape)>) {
+        paths.push((path.clone(), self));
+        for (key, field) in &self.fields {
+            field.collect_paths(path.join(Segment::Key(key.clone())), paths);
+        }
+        if let Some(items) = &self.items {
+            items.collect_paths(path.join(Segment::AnyIndex), paths);
+        }
+    }
+
+    // find or create the shape at a normalized path
+    fn entry(&mut self, path: &JsonPath) -> &mut Shape {
+        let mut shape = self;
+        for segment in &path.0 {
+            shape = match segment {
+                Segment::Key(key) => shape.fields.entry(key.clone()).or_default(),
+                Segment::Index(_) | Segment::AnyIndex => {
+                    shape.items.get_or_insert_with(Default::default)
+                }
+            };
+        }
+        shape
+    }
+
+    // record a single value at this path, without descending into it
+    fn record(&mut self, value: &Value, max_examples: usize, formats: &[Format]) {
+        let kind = Kind::of(value);
+        self.count += 1;
+        *self.kinds.entry(kind).or_default() += 1;
+        match value {
+            Value::String(s) => {
+                if let Some(format) = classify(formats, s) {
+                    *self.formats.entry(*format).or_default() += 1;
+                }
+            }
+            Value::Number(n) => self.numbers.record(n),
+            _ => {}
+        }
+        if kind.is_scalar() {
+            self.add_example(value, max_examples);
+        }
+    }
+
+    fn add_example(&mut self, value: &Value, max_examples: usize) {
+        if self.examples.len() < max_examples && !self.examples.contains(value) {
+            self.examples.push(value.clone());
+        }
+    }
+
+    /// Merge the observations of another shape into this one, keeping up to
+    /// `max_examples` distinct examples.
+    pub fn merge(&mut self, other: &Shape, max_examples: usize) {
+        self.count += other.count;
+        for (kind, count) in &other.kinds {
+            *self.kinds.entry(*kind).or_default() += count;
+        }
+        for example in &other.examples {
+            self.add_example(example, max_examples);
+        }
+        for (format, count) in &other.formats {
+            *self.formats.entry(*format).or_default() += count;
+        }
+        self.numbers.merge(&other.numbers);
+        for (key, field) in &other.fields {
+            self.fields
+                .entry(key.clone())
+                .or_default()
+                .merge(field, max_examples);
+        }
+        if let Some(items) = &other.items {
+            self.items
+                .get_or_insert_with(Default::default)
+                .merge(items, max_examples);
+        }
+    }
+}
+
+/// A shape displays as the number of values observed, and the frequency of
+/// each kind: `3 (integer: 2, null: 1)`. The frequency of each format follows
+/// that of strings: `2 (string: 2 [date: 1, uuid: 1])`.
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kinds: Vec<_> = self
+            .kinds
+            .iter()
+            .map(|(kind, count)| match kind {
+                Kind::String if !self.formats.is_empty() => {
+                    let formats: Vec<_> = self
+                        .formats
+                        .iter()
+                        .map(|(format, count)| format!("{}: {}", format, count))
+                        .collect();
+                    format!("{}: {} [{}]", kind, count, formats.join(", "))
+                }
+                _ => format!("{}: {}", kind, count),
+            })
+            .collect();
+        write!(f, "{} ({})", self.count, kinds.join(", "))
+    }
+}
+
+/// The merged shape of a corpus of JSON documents.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Schema {
+    root: Shape,
+    max_examples: usize,
+    formats: Vec<Format>,
+}
+
+impl Default for Schema {
+    fn default() -> Self {
+        Schema::with_max_examples(DEFAULT_MAX_EXAMPLES)
+    }
+}
+
+impl Schema {
+    /// Create an empty schema.
+    pub fn new() -> Schema {
+        Schema::default()
+    }
+
+    /// Create an empty schema which keeps up to `max_examples` distinct examples per path.
+    pub fn with_max_examples(max_examples: usize) -> Schema {
+        Schema {
+            root: Shape::default(),
+            max_examples,
+            formats: DEFAULT_FORMATS.to_vec(),
+        }
+    }
+
+    /// Set the formats by which strings are classified, in order of precedence.
+    ///
+    /// Each string is counted as the first format it matches, if any.
+    pub fn formats<I>(mut self, formats: I) -> Schema
+    where
+        I: IntoIterator<Item = Format>,
+    {
+        self.formats = formats.into_iter().collect();
+        self
+    }
+
+    /// The shape of the document roots.
+    pub fn root(&self) -> &Shape {
+        &self.root
+    }
+
+    /// How many documents have been observed.
+    pub fn documents(&self) -> usize {
+        self.root.count
+    }
+
+    /// Look up the shape at a path. Array indices are treated as `[*]`.
+    pub fn get(&self, path: &JsonPath) -> Option<&Shape> {
+        self.root.get(path)
+    }
+
+    /// Every shape in this schema, paired with its normalized path.
+    ///
+    /// Parents precede their children; object members precede array items.
+    pub fn paths(&self) -> Vec<(JsonPath, &Shape)> {
+        self.root.paths()
+    }
+
+    /// Record every value within `document`.
+    pub fn observe(&mut self, document: &Value) {
+        document.introspect(self.recorder());
+    }
+
+    /// Record every value within each of a sequence of whitespace-separated
+    /// JSON documents in `reader`, such as JSON Lines, returning the number
+    /// of documents.
+    ///
+    /// The documents are [streamed](super::stream), so they need not fit in
+    /// memory.
+    pub fn observe_reader<R: io::Read>(&mut self, reader: R) -> serde_json::Result<usize> {
+        super::stream::introspect_documents(reader, &Options::default(), self.recorder())
+    }
+
+    // a visitor which records each value visited
+    fn recorder(&mut self) -> impl FnMut(&Breadcrumbs, &dyn Any) + '_ {
+        let root = &mut self.root;
+        let max_examples = self.max_examples;
+        let formats = &self.formats;
+        move |breadcrumbs, visit| {
+            if let Some(value) = visit.downcast_ref::<Value>() {
+                let path = JsonPath::from_breadcrumbs(breadcrumbs);
+                root.entry(&path).record(value, max_examples, formats);
+            }
+        }
+    }
+
+    /// Merge the observations of another schema into this one.
+    ///
+    /// This is equivalent to having observed all of its documents.
+    pub fn merge(&mut self, other: &Schema) {
+        self.root.merge(&other.root, self.max_examples);
+    }
+}
+
+/// A schema displays each of its paths on its own line, followed by the shape
+/// there: `$.a[*]: 3 (integer: 2, null: 1)`.
+impl fmt::Display for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (path, shape) in self.paths() {
+            writeln!(f, "{}: {}", path, shape)?;
+        }
+        Ok(())
+    }
+}
+
+/// Infer a schema from a corpus of documents.
+pub fn infer<'a, I>(documents: I) -> Schema
+where
+    I: IntoIterator<Item = &'a Value>,
+{
+    let mut schema = Schema::new();
+    for document in documents {
+        schema.observe(document);
+    }
+    schema
+}
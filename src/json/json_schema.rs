@@ -0,0 +1,305 @@
+//! Export shapes as [JSON Schema](https://json-schema.org/) (draft 2020-12).
+//!
+//! Both inferred [`Schema`]s and the static shapes of [`TypeInfo`] types can
+//! be exported, so that data contracts can be shared with consumers which do
+//! not use Rust.
+//!
+//! An inferred schema describes exactly what was observed: each member
+//! present in every object observed is required, and strings which all had
+//! the same [`Format`](super::format::Format) have that `format`.
+//!
+//! A type is described as `serde` would serialize it by default, without
+//! regard to any `#[serde]` attributes: structs are objects, enums are
+//! externally tagged, `Option` fields are not required, and maps are objects
+//! whose values are all described by `additionalProperties`. Each named type
+//! is defined once within `$defs`, so recursive types are supported.
+
+use super::infer::{Kind, Schema, Shape};
+use crate::type_info::{Fields, Primitive, Type, TypeKind, TypeRef};
+use crate::TypeInfo;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashSet};
+
+/// The URI identifying the JSON Schema dialect produced.
+pub const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+// formats which have the same name as a JSON Schema `format`
+const FORMATS: &[&str] = &["date-time", "date", "uuid", "uri", "email"];
+
+/// Describe an inferred schema as a JSON Schema.
+pub fn from_inferred(schema: &Schema) -> Value {
+    let mut out = Map::new();
+    out.insert("$schema".to_string(), json!(DIALECT));
+    if let Value::Object(root) = shape_schema(schema.root()) {
+        out.extend(root);
+    }
+    Value::Object(out)
+}
+
+fn shape_schema(shape: &Shape) -> Value {
+    let mut out = Map::new();
+    let types: Vec<&str> = shape
+        .kinds
+        .keys()
+        .filter(|&&kind| !(kind == Kind::Integer && shape.kind_count(Kind::Float) > 0))
+        .map(|kind| match kind {
+            Kind::Null => "null",
+            Kind::Bool => "boolean",
+            Kind::Integer => "integer",
+            Kind::Float => "number",
+            Kind::String => "string",
+            Kind::Array => "array",
+            Kind::Object => "object",
+        })
+        .collect();
+    match types.as_slice() {
+        [] => return json!({}),
+        [ty] => out.insert("type".to_string(), json!(ty)),
+        _ => out.insert("type".to_string(), json!(types)),
+    };
+
+    if let Some(format) = shape.format() {
+        if FORMATS.contains(&format.name) {
+            out.insert("format".to_string(), json!(format.name));
+        } else if format.name == "base64" {
+            out.insert("contentEncoding".to_string(), json!("base64"));
+        }
+    }
+    if let Some(items) = &shape.items {
+        out.insert("items".to_string(), shape_schema(items));
+    }
+    if shape.kind_count(Kind::Object) > 0 {
+        let properties: Map<String, Value> = shape
+            .fields
+            .iter()
+            .map(|(key, field)| (key.clone(), shape_schema(field)))
+            .collect();
+        let required: Vec<&String> = shape
+            .fields
+            .keys()
+            .filter(|key| shape.is_required(key))
+            .collect();
+        out.insert("properties".to_string(), Value::Object(properties));
+        if !required.is_empty() {
+            out.insert("required".to_string(), json!(required));
+        }
+    }
+    if !shape.examples.is_empty() {
+        out.insert("examples".to_string(), json!(shape.examples));
+    }
+    Value::Object(out)
+}
+
+/// Describe the type `T` as a JSON Schema.
+pub fn from_type<T: TypeInfo + ?Sized>() -> Value {
+    from_type_info(&T::type_info())
+}
+
+/// Describe a type as a JSON Schema.
+pub fn from_type_info(ty: &Type) -> Value {
+    let mut context = Context {
+        root: ty.name,
+        names: BTreeMap::new(),
+        used_names: HashSet::new(),
+        defs: Map::new(),
+    };
+    let root = context.body(ty);
+
+    let mut out = Map::new();
+    out.insert("$schema".to_string(), json!(DIALECT));
+    if let Value::Object(root) = root {
+        out.extend(root);
+    }
+    if !context.defs.is_empty() {
+        out.insert("$defs".to_string(), Value::Object(context.defs));
+    }
+    Value::Object(out)
+}
+
+struct Context {
+    root: &'static str,
+    // the name in `$defs` of each named type, by its type name
+    names: BTreeMap<&'static str, String>,
+    used_names: HashSet<String>,
+    defs: Map<String, Value>,
+}
+
+impl Context {
+    // a schema for the referenced type, which may be a reference to a definition
+    fn schema(&mut self, ty: TypeRef) -> Value {
+        let ty = ty.get();
+        if !matches!(ty.kind, TypeKind::Struct(_) | TypeKind::Enum(_)) {
+            return self.body(&ty);
+        }
+        if ty.name == self.root {
+            return json!({"$ref": "#"});
+        }
+        if let Some(name) = self.names.get(ty.name) {
+            return json!({ "$ref": format!("#/$defs/{}", name) });
+        }
+
+        let mut name = def_name(ty.name);
+        let mut n = 2;
+        while self.used_names.contains(&name) {
+            name = format!("{}{}", def_name(ty.name), n);
+            n += 1;
+        }
+        self.used_names.insert(name.clone());
+        // record the name before describing the body, which may refer to it
+        self.names.insert(ty.name, name.clone());
+        let body = self.body(&ty);
+        self.defs.insert(name.clone(), body);
+        json!({ "$ref": format!("#/$defs/{}", name) })
+    }
+
+    // a schema describing the type itself, never a reference to it
+    fn body(&mut self, ty: &Type) -> Value {
+        match &ty.kind {
+            TypeKind::Primitive(primitive) => primitive_schema(*primitive),
+            TypeKind::Struct(fields) => self.fields(fields),
+            TypeKind::Enum(variants) => {
+                let unit: Vec<&str> = variants
+                    .iter()
+                    .filter(|variant| matches!(variant.fields, Fields::Unit))
+                    .map(|variant| variant.name)
+                    .collect();
+                let mut one_of = Vec::new();
+                if !unit.is_empty() {
+                    one_of.push(json!({"type": "string", "enum": unit}));
+                }
+                for variant in variants {
+                    if !matches!(variant.fields, Fields::Unit) {
+                        let fields = self.fields(&variant.fields);
+                        one_of.push(tagged(variant.name, fields));
+                    }
+                }
+                match one_of.len() {
+                    0 => json!({"not": {}}),
+                    1 => one_of.remove(0),
+                    _ => json!({ "oneOf": one_of }),
+                }
+            }
+            TypeKind::Tuple(items) => self.tuple(items),
+            TypeKind::Array(item, len) => json!({
+                "type": "array",
+                "items": self.schema(*item),
+                "minItems": len,
+                "maxItems": len,
+            }),
+            TypeKind::List(item) => json!({"type": "array", "items": self.schema(*item)}),
+            TypeKind::Set(item) => json!({
+                "type": "array",
+                "items": self.schema(*item),
+                "uniqueItems": true,
+            }),
+            TypeKind::Map(_, value) => json!({
+                "type": "object",
+                "additionalProperties": self.schema(*value),
+            }),
+            TypeKind::Option(some) => json!({"anyOf": [self.schema(*some), {"type": "null"}]}),
+            TypeKind::Result(ok, err) => json!({
+                "oneOf": [tagged("Ok", self.schema(*ok)), tagged("Err", self.schema(*err))],
+            }),
+            TypeKind::Json | TypeKind::Opaque => json!({}),
+        }
+    }
+
+    fn fields(&mut self, fields: &Fields) -> Value {
+        match fields {
+            Fields::Unit => json!({"type": "null"}),
+            Fields::Named(fields) => {
+                let mut properties = Map::new();
+                let mut required = Vec::new();
+                for field in fields {
+                    properties.insert(field.name.to_string(), self.schema(field.ty));
+                    if !matches!(field.ty.get().kind, TypeKind::Option(_)) {
+                        required.push(field.name);
+                    }
+                }
+                let mut out = json!({"type": "object", "properties": properties});
+                if !required.is_empty() {
+                    out["required"] = json!(required);
+                }
+                out
+            }
+            // a newtype is serialized as its contents
+            Fields::Unnamed(fields) if fields.len() == 1 => self.schema(fields[0]),
+            Fields::Unnamed(fields) if fields.is_empty() => json!({"type": "array", "maxItems": 0}),
+            Fields::Unnamed(fields) => self.tuple(fields),
+        }
+    }
+
+    fn tuple(&mut self, items: &[TypeRef]) -> Value {
+        if items.is_empty() {
+            return json!({"type": "null"});
+        }
+        let prefix: Vec<Value> = items.iter().map(|item| self.schema(*item)).collect();
+        json!({
+            "type": "array",
+            "prefixItems": prefix,
+            "items": false,
+            "minItems": items.len(),
+        })
+    }
+}
+
+// an object whose only member is `name`, as serde externally tags enum variants
+fn tagged(name: &str, value: Value) -> Value {
+    json!({
+        "type": "object",
+        "properties": { name: value },
+        "required": [name],
+        "additionalProperties": false,
+    })
+}
+
+fn primitive_schema(primitive: Primitive) -> Value {
+    let integer = |minimum: Option<Value>, maximum: Option<Value>| {
+        let mut out = json!({"type": "integer"});
+        if let Some(minimum) = minimum {
+            out["minimum"] = minimum;
+        }
+        if let Some(maximum) = maximum {
+            out["maximum"] = maximum;
+        }
+        out
+    };
+    match primitive {
+        Primitive::Bool => json!({"type": "boolean"}),
+        Primitive::Char => json!({"type": "string", "minLength": 1, "maxLength": 1}),
+        Primitive::String => json!({"type": "string"}),
+        Primitive::U8 => integer(Some(json!(0)), Some(json!(u8::MAX))),
+        Primitive::U16 => integer(Some(json!(0)), Some(json!(u16::MAX))),
+        Primitive::U32 => integer(Some(json!(0)), Some(json!(u32::MAX))),
+        Primitive::U64 | Primitive::Usize => integer(Some(json!(0)), Some(json!(u64::MAX))),
+        Primitive::U128 => integer(Some(json!(0)), None),
+        Primitive::I8 => integer(Some(json!(i8::MIN)), Some(json!(i8::MAX))),
+        Primitive::I16 => integer(Some(json!(i16::MIN)), Some(json!(i16::MAX))),
+        Primitive::I32 => integer(Some(json!(i32::MIN)), Some(json!(i32::MAX))),
+        Primitive::I64 | Primitive::Isize => integer(Some(json!(i64::MIN)), Some(json!(i64::MAX))),
+        Primitive::I128 => integer(None, None),
+        Primitive::F32 | Primitive::F64 | Primitive::Number => json!({"type": "number"}),
+    }
+}
+
+// a name for a definition, from a type name: `a::Foo<b::Bar, u8>` becomes `Foo_Bar_u8`
+fn def_name(type_name: &str) -> String {
+    let mut words: Vec<String> = Vec::new();
+    let mut word = String::new();
+    let mut chars = type_name.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_alphanumeric() || c == '_' {
+            word.push(c);
+        } else if c == ':' && chars.peek() == Some(&':') {
+            // drop the path leading to the name
+            chars.next();
+            word.clear();
+        } else if !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words.join("_")
+}
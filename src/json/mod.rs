@@ -0,0 +1,15 @@
+//! Tools for exploring JSON, built on the `Introspect` implementation for
+//! `serde_json::Value`.
+
+pub mod codegen;
+pub mod format;
+pub mod infer;
+pub mod json_schema;
+pub mod number;
+pub mod patch;
+mod path;
+pub mod report;
+pub mod stream;
+pub mod validate;
+
+pub use path::{JsonPath, Segment};
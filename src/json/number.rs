@@ -0,0 +1,128 @@
+//! Aggregate the numbers observed at a path, to choose a Rust numeric type.
+//!
+//! JSON has a single number type, but Rust has many. [`Numbers`] records the
+//! range of the numbers observed, whether any were negative or fractional,
+//! and whether any would lose precision as a float, from which it recommends
+//! the narrowest Rust type able to represent all of them.
+
+use serde_json::Number;
+
+/// Integer types, narrowest first, with their bounds.
+const INTEGERS: &[(&str, i128, i128)] = &[
+    ("u8", 0, u8::MAX as i128),
+    ("i8", i8::MIN as i128, i8::MAX as i128),
+    ("u16", 0, u16::MAX as i128),
+    ("i16", i16::MIN as i128, i16::MAX as i128),
+    ("u32", 0, u32::MAX as i128),
+    ("i32", i32::MIN as i128, i32::MAX as i128),
+    ("u64", 0, u64::MAX as i128),
+    ("i64", i64::MIN as i128, i64::MAX as i128),
+    ("i128", i128::MIN, i128::MAX),
+];
+
+/// Aggregated observations of the numbers found at a particular path.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Numbers {
+    /// How many integers were observed.
+    pub integers: usize,
+    /// How many floats were observed, including those with no fractional part.
+    pub floats: usize,
+    /// The least and greatest integers observed.
+    pub integer_range: Option<(i128, i128)>,
+    /// The least and greatest of all numbers observed, as floats.
+    pub range: Option<(f64, f64)>,
+    /// How many integers cannot be represented exactly by an `f64`.
+    pub inexact_in_f64: usize,
+    /// How many numbers cannot be represented exactly by an `f32`.
+    pub inexact_in_f32: usize,
+}
+
+impl Numbers {
+    /// Record a single number.
+    pub fn record(&mut self, number: &Number) {
+        let float = number.as_f64().unwrap_or_default();
+        self.range = Some(match self.range {
+            Some((min, max)) => (min.min(float), max.max(float)),
+            None => (float, float),
+        });
+
+        let integer = match (number.as_u64(), number.as_i64()) {
+            (Some(n), _) => n as i128,
+            (None, Some(n)) => n as i128,
+            (None, None) => {
+                self.floats += 1;
+                if float as f32 as f64 != float {
+                    self.inexact_in_f32 += 1;
+                }
+                return;
+            }
+        };
+        self.integers += 1;
+        self.integer_range = Some(match self.integer_range {
+            Some((min, max)) => (min.min(integer), max.max(integer)),
+            None => (integer, integer),
+        });
+        if float as i128 != integer {
+            self.inexact_in_f64 += 1;
+        }
+        if float as f32 as i128 != integer {
+            self.inexact_in_f32 += 1;
+        }
+    }
+
+    /// Merge the observations of another aggregate into this one.
+    pub fn merge(&mut self, other: &Numbers) {
+        self.integers += other.integers;
+        self.floats += other.floats;
+        self.integer_range = match (self.integer_range, other.integer_range) {
+            (Some((a, b)), Some((c, d))) => Some((a.min(c), b.max(d))),
+            (a, b) => a.or(b),
+        };
+        self.range = match (self.range, other.range) {
+            (Some((a, b)), Some((c, d))) => Some((a.min(c), b.max(d))),
+            (a, b) => a.or(b),
+        };
+        self.inexact_in_f64 += other.inexact_in_f64;
+        self.inexact_in_f32 += other.inexact_in_f32;
+    }
+
+    /// How many numbers were observed.
+    pub fn count(&self) -> usize {
+        self.integers + self.floats
+    }
+
+    /// True if any number observed was negative.
+    pub fn is_signed(&self) -> bool {
+        self.range.is_some_and(|(min, _)| min < 0.0)
+    }
+
+    /// The narrowest Rust numeric type which represents every number observed,
+    /// or `None` if none were.
+    ///
+    /// If any float was observed, this is a float type, even if every float
+    /// had no fractional part, as they cannot be deserialized as integers.
+    /// It is `f32` only if that represents every number exactly.
+    pub fn narrowest_type(&self) -> Option<&'static str> {
+        match self.floats {
+            0 => self.narrowest_integer_type(),
+            _ if self.inexact_in_f32 == 0 => Some("f32"),
+            _ => Some("f64"),
+        }
+    }
+
+    /// The narrowest Rust integer type which represents every integer
+    /// observed, ignoring floats, or `None` if none were.
+    pub fn narrowest_integer_type(&self) -> Option<&'static str> {
+        let (min, max) = self.integer_range?;
+        INTEGERS
+            .iter()
+            .find(|(_, lower, upper)| *lower <= min && max <= *upper)
+            .map(|(name, _, _)| *name)
+    }
+
+    /// True if some integer observed would lose precision when represented,
+    /// along with the floats observed, as an `f64`.
+    pub fn loses_precision(&self) -> bool {
+        self.floats > 0 && self.inexact_in_f64 > 0
+    }
+}
@@ -0,0 +1,450 @@
+//! Compute and apply [JSON Patch](https://www.rfc-editor.org/rfc/rfc6902)
+//! documents.
+//!
+//! [`diff`] produces a [`Patch`] which transforms one `serde_json::Value`
+//! into another, using only `add`, `remove` and `replace` operations. Objects
+//! are compared member by member and arrays item by item; items beyond the
+//! end of the shorter array are added or removed, from the last backwards.
+//!
+//! [`apply`] applies a patch, supporting every operation of RFC 6902. A patch
+//! is applied atomically: if any operation fails, the value is left
+//! unchanged, and the [`PatchError`] is located by the `Breadcrumbs` and
+//! [`JsonPath`] of the value at which the operation failed.
+
+use super::{
+    path::Location,
+    validate::{json_eq, type_name},
+    JsonPath,
+};
+use crate::Breadcrumbs;
+use serde_json::{json, Value};
+use std::{fmt, io};
+
+/// A single JSON Patch operation. Paths are JSON Pointers (RFC 6901).
+#[derive(Clone, PartialEq, Debug)]
+pub enum Operation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+impl Operation {
+    /// The name of the operation, such as `add`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operation::Add { .. } => "add",
+            Operation::Remove { .. } => "remove",
+            Operation::Replace { .. } => "replace",
+            Operation::Move { .. } => "move",
+            Operation::Copy { .. } => "copy",
+            Operation::Test { .. } => "test",
+        }
+    }
+
+    /// The JSON Pointer to the location which the operation targets.
+    pub fn path(&self) -> &str {
+        match self {
+            Operation::Add { path, .. }
+            | Operation::Remove { path }
+            | Operation::Replace { path, .. }
+            | Operation::Move { path, .. }
+            | Operation::Copy { path, .. }
+            | Operation::Test { path, .. } => path,
+        }
+    }
+
+    /// Represent this operation as a JSON object.
+    pub fn to_json(&self) -> Value {
+        let mut out = json!({"op": self.name(), "path": self.path()});
+        match self {
+            Operation::Add { value, .. }
+            | Operation::Replace { value, .. }
+            | Operation::Test { value, .. } => out["value"] = value.clone(),
+            Operation::Move { from, .. } | Operation::Copy { from, .. } => {
+                out["from"] = json!(from)
+            }
+            Operation::Remove { .. } => {}
+        }
+        out
+    }
+
+    /// Read an operation from its JSON object.
+    pub fn from_json(value: &Value) -> serde_json::Result<Operation> {
+        let member = |name: &str| {
+            value
+                .get(name)
+                .ok_or_else(|| invalid(format!("operation has no \"{}\"", name)))
+        };
+        let pointer = |name: &str| {
+            member(name)?
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| invalid(format!("\"{}\" of operation is not a string", name)))
+        };
+        let op = member("op")?
+            .as_str()
+            .ok_or_else(|| invalid("\"op\" of operation is not a string".to_string()))?;
+        let path = pointer("path")?;
+        Ok(match op {
+            "add" => Operation::Add {
+                path,
+                value: member("value")?.clone(),
+            },
+            "remove" => Operation::Remove { path },
+            "replace" => Operation::Replace {
+                path,
+                value: member("value")?.clone(),
+            },
+            "move" => Operation::Move {
+                from: pointer("from")?,
+                path,
+            },
+            "copy" => Operation::Copy {
+                from: pointer("from")?,
+                path,
+            },
+            "test" => Operation::Test {
+                path,
+                value: member("value")?.clone(),
+            },
+            other => return Err(invalid(format!("unknown operation \"{}\"", other))),
+        })
+    }
+}
+
+fn invalid(message: String) -> serde_json::Error {
+    serde::de::Error::custom(message)
+}
+
+/// A JSON Patch document: a sequence of operations applied in order.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Patch(pub Vec<Operation>);
+
+impl Patch {
+    /// True if the patch has no operations.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Represent this patch as a JSON array.
+    pub fn to_json(&self) -> Value {
+        self.0.iter().map(Operation::to_json).collect()
+    }
+
+    /// Read a patch from its JSON array.
+    pub fn from_json(value: &Value) -> serde_json::Result<Patch> {
+        value
+            .as_array()
+            .ok_or_else(|| invalid("a patch must be an array".to_string()))?
+            .iter()
+            .map(Operation::from_json)
+            .collect::<serde_json::Result<_>>()
+            .map(Patch)
+    }
+
+    /// Read a patch document from `reader`.
+    pub fn from_reader<R: io::Read>(reader: R) -> serde_json::Result<Patch> {
+        Patch::from_json(&serde_json::from_reader(io::BufReader::new(reader))?)
+    }
+}
+
+/// Compute a patch which transforms `old` into `new`.
+pub fn diff(old: &Value, new: &Value) -> Patch {
+    let mut operations = Vec::new();
+    diff_into(old, new, &mut String::new(), &mut operations);
+    Patch(operations)
+}
+
+/// Compute a patch which transforms the JSON serialization of `old` into
+/// that of `new`.
+pub fn diff_serialize<T>(old: &T, new: &T) -> serde_json::Result<Patch>
+where
+    T: serde::Serialize + ?Sized,
+{
+    Ok(diff(
+        &serde_json::to_value(old)?,
+        &serde_json::to_value(new)?,
+    ))
+}
+
+// `pointer` is the location of `old`, restored before returning
+fn diff_into(old: &Value, new: &Value, pointer: &mut String, operations: &mut Vec<Operation>) {
+    if old == new {
+        return;
+    }
+    let len = pointer.len();
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for (key, old_value) in old {
+                push_token(pointer, key);
+                match new.get(key) {
+                    Some(new_value) => diff_into(old_value, new_value, pointer, operations),
+                    None => operations.push(Operation::Remove {
+                        path: pointer.clone(),
+                    }),
+                }
+                pointer.truncate(len);
+            }
+            for (key, new_value) in new {
+                if !old.contains_key(key) {
+                    push_token(pointer, key);
+                    operations.push(Operation::Add {
+                        path: pointer.clone(),
+                        value: new_value.clone(),
+                    });
+                    pointer.truncate(len);
+                }
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => {
+            for (idx, (old_item, new_item)) in old.iter().zip(new).enumerate() {
+                push_token(pointer, &idx.to_string());
+                diff_into(old_item, new_item, pointer, operations);
+                pointer.truncate(len);
+            }
+            for idx in (new.len()..old.len()).rev() {
+                push_token(pointer, &idx.to_string());
+                operations.push(Operation::Remove {
+                    path: pointer.clone(),
+                });
+                pointer.truncate(len);
+            }
+            for (idx, new_item) in new.iter().enumerate().skip(old.len()) {
+                push_token(pointer, &idx.to_string());
+                operations.push(Operation::Add {
+                    path: pointer.clone(),
+                    value: new_item.clone(),
+                });
+                pointer.truncate(len);
+            }
+        }
+        _ => operations.push(Operation::Replace {
+            path: pointer.clone(),
+            value: new.clone(),
+        }),
+    }
+}
+
+fn push_token(pointer: &mut String, token: &str) {
+    pointer.push('/');
+    pointer.push_str(&token.replace('~', "~0").replace('/', "~1"));
+}
+
+/// Why an operation of a patch could not be applied.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PatchError {
+    /// The index of the failed operation within the patch.
+    pub operation: usize,
+    /// The breadcrumbs by which the node at which the operation failed is
+    /// visited, from the root `serde_json::Value`.
+    pub breadcrumbs: Breadcrumbs,
+    /// The path to the node at which the operation failed.
+    pub path: JsonPath,
+    /// A description of the failure.
+    pub message: String,
+}
+
+/// A patch error displays as the operation, its location and its message:
+/// `operation 1 at $.a: no member "b"`.
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "operation {} at {}: {}",
+            self.operation, self.path, self.message
+        )
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+// a failure located within the value, before it is attributed to an operation
+struct Failure {
+    at: Location,
+    message: String,
+}
+
+fn fail<T>(at: &Location, message: String) -> Result<T, Failure> {
+    Err(Failure {
+        at: at.clone(),
+        message,
+    })
+}
+
+/// Apply `patch` to `value`. If any operation fails, `value` is unchanged.
+pub fn apply(value: &mut Value, patch: &Patch) -> Result<(), PatchError> {
+    let mut patched = value.clone();
+    for (idx, operation) in patch.0.iter().enumerate() {
+        apply_operation(&mut patched, operation).map_err(|failure| PatchError {
+            operation: idx,
+            breadcrumbs: failure.at.breadcrumbs,
+            path: failure.at.path,
+            message: failure.message,
+        })?;
+    }
+    *value = patched;
+    Ok(())
+}
+
+fn apply_operation(root: &mut Value, operation: &Operation) -> Result<(), Failure> {
+    match operation {
+        Operation::Add { path, value } => add(root, &parse_pointer(path)?, value.clone()),
+        Operation::Remove { path } => remove(root, &parse_pointer(path)?).map(drop),
+        Operation::Replace { path, value } => {
+            let (target, _) = resolve(root, &parse_pointer(path)?)?;
+            *target = value.clone();
+            Ok(())
+        }
+        Operation::Move { from, path } => {
+            let (from_tokens, tokens) = (parse_pointer(from)?, parse_pointer(path)?);
+            if tokens.len() > from_tokens.len() && tokens.starts_with(&from_tokens) {
+                let (_, at) = resolve(root, &from_tokens)?;
+                return fail(&at, "cannot move a value into itself".to_string());
+            }
+            let value = remove(root, &from_tokens)?;
+            add(root, &tokens, value)
+        }
+        Operation::Copy { from, path } => {
+            let (value, _) = resolve(root, &parse_pointer(from)?)?;
+            let value = value.clone();
+            add(root, &parse_pointer(path)?, value)
+        }
+        Operation::Test { path, value } => {
+            let (target, at) = resolve(root, &parse_pointer(path)?)?;
+            if json_eq(target, value) {
+                Ok(())
+            } else {
+                fail(
+                    &at,
+                    format!("test failed: expected {}, found {}", value, target),
+                )
+            }
+        }
+    }
+}
+
+// the unescaped reference tokens of a JSON Pointer
+fn parse_pointer(pointer: &str) -> Result<Vec<String>, Failure> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let invalid = || {
+        fail(
+            &Location::default(),
+            format!("invalid JSON Pointer {:?}", pointer),
+        )
+    };
+    if !pointer.starts_with('/') {
+        return invalid();
+    }
+    let mut tokens = Vec::new();
+    for token in pointer[1..].split('/') {
+        let mut unescaped = String::with_capacity(token.len());
+        let mut chars = token.chars();
+        while let Some(c) = chars.next() {
+            if c != '~' {
+                unescaped.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('0') => unescaped.push('~'),
+                Some('1') => unescaped.push('/'),
+                _ => return invalid(),
+            }
+        }
+        tokens.push(unescaped);
+    }
+    Ok(tokens)
+}
+
+// the index into an array of length `len` named by `token`; when `append`,
+// this may be the length itself, also named `-`
+fn parse_index(token: &str, len: usize, append: bool, at: &Location) -> Result<usize, Failure> {
+    if append && token == "-" {
+        return Ok(len);
+    }
+    let well_formed = !token.is_empty()
+        && token.bytes().all(|b| b.is_ascii_digit())
+        && (token == "0" || !token.starts_with('0'));
+    match token.parse::<usize>() {
+        Ok(idx) if well_formed && (idx < len || (append && idx == len)) => Ok(idx),
+        Ok(_) if well_formed => fail(at, format!("index {} is out of bounds", token)),
+        _ => fail(at, format!("{:?} is not an array index", token)),
+    }
+}
+
+// the value at `tokens`, and its location
+fn resolve<'v>(
+    root: &'v mut Value,
+    tokens: &[String],
+) -> Result<(&'v mut Value, Location), Failure> {
+    let mut value = root;
+    let mut at = Location::default();
+    for token in tokens {
+        value = match value {
+            Value::Object(map) => match map.get_mut(token) {
+                Some(member) => {
+                    at = at.member(token);
+                    member
+                }
+                None => return fail(&at, format!("no member {:?}", token)),
+            },
+            Value::Array(items) => {
+                let idx = parse_index(token, items.len(), false, &at)?;
+                at = at.item(idx);
+                &mut items[idx]
+            }
+            other => {
+                return fail(
+                    &at,
+                    format!("cannot find {:?} within {}", token, type_name(other)),
+                )
+            }
+        };
+    }
+    Ok((value, at))
+}
+
+fn add(root: &mut Value, tokens: &[String], value: Value) -> Result<(), Failure> {
+    let (token, init) = match tokens.split_last() {
+        Some(split) => split,
+        None => {
+            *root = value;
+            return Ok(());
+        }
+    };
+    let (parent, at) = resolve(root, init)?;
+    match parent {
+        Value::Object(map) => {
+            map.insert(token.to_string(), value);
+        }
+        Value::Array(items) => {
+            let idx = parse_index(token, items.len(), true, &at)?;
+            items.insert(idx, value);
+        }
+        other => return fail(&at, format!("cannot add to {}", type_name(other))),
+    }
+    Ok(())
+}
+
+fn remove(root: &mut Value, tokens: &[String]) -> Result<Value, Failure> {
+    let (token, init) = match tokens.split_last() {
+        Some(split) => split,
+        None => return fail(&Location::default(), "cannot remove the root".to_string()),
+    };
+    let (parent, at) = resolve(root, init)?;
+    match parent {
+        Value::Object(map) => match map.remove(token) {
+            Some(value) => Ok(value),
+            None => fail(&at, format!("no member {:?}", token)),
+        },
+        Value::Array(items) => {
+            let idx = parse_index(token, items.len(), false, &at)?;
+            Ok(items.remove(idx))
+        }
+        other => fail(&at, format!("cannot remove from {}", type_name(other))),
+    }
+}
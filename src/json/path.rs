@@ -0,0 +1,155 @@
+use crate::{Breadcrumb, Breadcrumbs};
+use std::fmt;
+
+/// One step of a [`JsonPath`].
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Segment {
+    /// A member of an object.
+    Key(String),
+    /// A particular item of an array.
+    Index(usize),
+    /// Any item of an array.
+    AnyIndex,
+}
+
+/// The location of a node within a JSON document, in terms of object keys and
+/// array indices.
+///
+/// This is more compact than the `Breadcrumbs` which lead to the same node,
+/// because it omits the `Breadcrumb::Variant` steps into each `serde_json::Value`.
+/// It displays in the style `$.foo[0]["bar baz"]`.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct JsonPath(pub Vec<Segment>);
+
+impl JsonPath {
+    /// The path of the root of a document.
+    pub fn root() -> JsonPath {
+        JsonPath::default()
+    }
+
+    /// Compute the path of a node from the breadcrumbs by which it was visited.
+    ///
+    /// The breadcrumbs must be relative to a `serde_json::Value`: an
+    /// `Index` which follows `Variant("Array")` is an array index, and any other
+    /// `Index` is an object key. All other breadcrumbs are ignored.
+    pub fn from_breadcrumbs(breadcrumbs: &Breadcrumbs) -> JsonPath {
+        let mut segments = Vec::new();
+        let mut in_array = false;
+        for breadcrumb in breadcrumbs {
+            match breadcrumb {
+                Breadcrumb::Variant(variant) => in_array = *variant == "Array",
+                Breadcrumb::Index(index) => {
+                    match index.parse() {
+                        Ok(index) if in_array => segments.push(Segment::Index(index)),
+                        _ => segments.push(Segment::Key(index.clone())),
+                    }
+                    in_array = false;
+                }
+                _ => {}
+            }
+        }
+        JsonPath(segments)
+    }
+
+    /// Replace every particular array index with `Segment::AnyIndex`.
+    pub fn normalized(&self) -> JsonPath {
+        JsonPath(
+            self.0
+                .iter()
+                .map(|segment| match segment {
+                    Segment::Index(_) => Segment::AnyIndex,
+                    other => other.clone(),
+                })
+                .collect(),
+        )
+    }
+
+    /// Format this path as a JSON Pointer (RFC 6901), such as `/foo/0/bar~1baz`.
+    ///
+    /// A JSON Pointer cannot refer to every item of an array, so
+    /// `Segment::AnyIndex` is written as `*`.
+    pub fn to_pointer(&self) -> String {
+        let mut pointer = String::new();
+        for segment in &self.0 {
+            pointer.push('/');
+            match segment {
+                Segment::Key(key) => pointer.push_str(&key.replace('~', "~0").replace('/', "~1")),
+                Segment::Index(index) => pointer.push_str(&index.to_string()),
+                Segment::AnyIndex => pointer.push('*'),
+            }
+        }
+        pointer
+    }
+
+    /// Extend this path by one segment.
+    pub fn join(&self, segment: Segment) -> JsonPath {
+        let mut segments = self.0.clone();
+        segments.push(segment);
+        JsonPath(segments)
+    }
+}
+
+// the location of a node within a `serde_json::Value`, both as the breadcrumbs
+// by which it is visited and as its path
+#[derive(Clone, Default)]
+pub(crate) struct Location {
+    pub(crate) breadcrumbs: Breadcrumbs,
+    pub(crate) path: JsonPath,
+}
+
+impl Location {
+    pub(crate) fn item(&self, index: usize) -> Location {
+        let mut breadcrumbs = self.breadcrumbs.clone();
+        breadcrumbs.push_back(Breadcrumb::Variant("Array"));
+        breadcrumbs.push_back(Breadcrumb::Index(format!("{}", index)));
+        Location {
+            breadcrumbs,
+            path: self.path.join(Segment::Index(index)),
+        }
+    }
+
+    pub(crate) fn member(&self, key: &str) -> Location {
+        let mut breadcrumbs = self.breadcrumbs.clone();
+        breadcrumbs.push_back(Breadcrumb::Variant("Object"));
+        breadcrumbs.push_back(Breadcrumb::Index(key.to_string()));
+        Location {
+            breadcrumbs,
+            path: self.path.join(Segment::Key(key.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Segment::Key(key) if is_identifier(key) => write!(f, ".{}", key),
+            Segment::Key(key) => write!(
+                f,
+                "[{}]",
+                serde_json::to_string(key).map_err(|_| fmt::Error)?
+            ),
+            Segment::Index(index) => write!(f, "[{}]", index),
+            Segment::AnyIndex => f.write_str("[*]"),
+        }
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("$")?;
+        for segment in &self.0 {
+            write!(f, "{}", segment)?;
+        }
+        Ok(())
+    }
+}
+
+// true if `key` can be displayed after a dot without ambiguity
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    match chars.next() {
+        Some(c) if c.is_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_alphanumeric() || c == '_')
+}
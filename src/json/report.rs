@@ -0,0 +1,111 @@
+//! Find the `serde_json::Value`s which remain within partially typed data.
+//!
+//! A common workflow is to deserialize into types which model the known parts
+//! of a document, with `Value` catch-alls for the rest, including
+//! `#[serde(flatten)]` maps of extra members. A [`Report`] introspects such
+//! typed data and gathers every outermost `Value` it contains, grouped by its
+//! normalized path: the path formatted by [`format_path`](crate::format_path),
+//! with every list or array index replaced by `*`. Map keys are kept, even
+//! those which are integers. The values found in each group are aggregated
+//! into a [`Schema`], which summarizes their kinds and structure.
+
+use super::infer::Schema;
+use crate::{Breadcrumb, Breadcrumbs, Introspect, Options};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+/// Report the `serde_json::Value`s within `root`.
+pub fn report<T: Introspect>(root: &T) -> Report {
+    let mut report = Report::new();
+    report.observe(root);
+    report
+}
+
+/// The `serde_json::Value`s found within some typed data, grouped by normalized path.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Report {
+    groups: BTreeMap<String, Schema>,
+}
+
+impl Report {
+    /// Create an empty report.
+    pub fn new() -> Report {
+        Report::default()
+    }
+
+    /// Gather the `serde_json::Value`s within `root` into this report.
+    ///
+    /// Values nested within other values are summarized by the schema of the
+    /// outermost value, and are not reported separately.
+    pub fn observe<T: Introspect>(&mut self, root: &T) {
+        let groups = &mut self.groups;
+        let mut outermost: Option<Breadcrumbs> = None;
+        // the trails of map entries, whose indices are keys rather than
+        // positions; each key is visited just before its value
+        let mut entries = HashSet::new();
+        let options = Options {
+            map_keys: true,
+            ..Options::default()
+        };
+        root.introspect_with(&options, |breadcrumbs, visit| {
+            if let Some(Breadcrumb::MapKey(key)) = breadcrumbs.last() {
+                let mut entry = breadcrumbs.clone();
+                entry.pop_back();
+                entry.push_back(Breadcrumb::Index(key.clone()));
+                entries.insert(entry);
+                return;
+            }
+            if let Some(outermost) = &outermost {
+                if breadcrumbs.len() > outermost.len()
+                    && breadcrumbs.iter().zip(outermost).all(|(a, b)| a == b)
+                {
+                    return;
+                }
+            }
+            if let Some(value) = visit.downcast_ref::<Value>() {
+                groups
+                    .entry(normalize(breadcrumbs, &entries))
+                    .or_default()
+                    .observe(value);
+                outermost = Some(breadcrumbs.clone());
+            }
+        });
+    }
+
+    /// The schema of the values found at each normalized path.
+    pub fn groups(&self) -> &BTreeMap<String, Schema> {
+        &self.groups
+    }
+
+    /// True if no values have been found.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+}
+
+/// Each group displays on its own line, as its path, the number of values
+/// found, and the frequency of each kind: `$.extra["a"]: 3 (integer: 2, null: 1)`.
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (path, schema) in &self.groups {
+            writeln!(f, "{}: {}", path, schema.root())?;
+        }
+        Ok(())
+    }
+}
+
+// Format `breadcrumbs` as a path, with every index which is not one of the
+// map `entries` replaced by `*`.
+fn normalize(breadcrumbs: &Breadcrumbs, entries: &HashSet<Breadcrumbs>) -> String {
+    let mut trail = Breadcrumbs::new();
+    let mut normalized = Breadcrumbs::new();
+    for breadcrumb in breadcrumbs {
+        trail.push_back(breadcrumb.clone());
+        normalized.push_back(match breadcrumb {
+            Breadcrumb::Index(_) if !entries.contains(&trail) => Breadcrumb::Index("*".to_string()),
+            other => other.clone(),
+        });
+    }
+    crate::format_path(&normalized)
+}
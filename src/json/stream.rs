@@ -0,0 +1,203 @@
+//! Introspect JSON directly from its serialized form, without first
+//! materializing it as a `serde_json::Value`.
+//!
+//! The visitor is called with exactly the same breadcrumbs as if the
+//! document had been parsed into a `Value` which was then introspected.
+//! However, members are visited in the order they appear in the document
+//! rather than in the order of a `serde_json::Map`, and a duplicated key is
+//! visited each time it appears rather than only the last time.
+//!
+//! Scalars are visited just as the `Value` implementation would visit them: a
+//! `Value`, then its contents. Arrays and objects are visited as values of
+//! the same types as well, a `Value` and then its `Vec` or `Map`, but these
+//! are always empty: arrays and objects are never held in memory, and their
+//! items are visited afterwards, as they are read.
+//!
+//! This allows introspecting documents far larger than available memory, as
+//! long as no single scalar within them is too large.
+
+use crate::{Breadcrumb, Breadcrumbs, Introspect, Options};
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess};
+use serde_json::{Deserializer, Map, Number, Value};
+use std::{any::Any, fmt, io};
+
+/// Introspect the single JSON document in `reader`.
+pub fn introspect_reader<R, F>(reader: R, options: &Options, mut visit: F) -> serde_json::Result<()>
+where
+    R: io::Read,
+    F: FnMut(&Breadcrumbs, &dyn Any),
+{
+    let mut deserializer = Deserializer::from_reader(reader);
+    walk(&mut deserializer, options, &mut visit)?;
+    deserializer.end()
+}
+
+/// Introspect the single JSON document in `json`.
+pub fn introspect_str<F>(json: &str, options: &Options, mut visit: F) -> serde_json::Result<()>
+where
+    F: FnMut(&Breadcrumbs, &dyn Any),
+{
+    let mut deserializer = Deserializer::from_str(json);
+    walk(&mut deserializer, options, &mut visit)?;
+    deserializer.end()
+}
+
+/// Introspect each of a sequence of whitespace-separated JSON documents in
+/// `reader`, such as JSON Lines, returning the number of documents.
+///
+/// Each document is introspected in turn, from empty breadcrumbs.
+pub fn introspect_documents<R, F>(
+    reader: R,
+    options: &Options,
+    mut visit: F,
+) -> serde_json::Result<usize>
+where
+    R: io::Read,
+    F: FnMut(&Breadcrumbs, &dyn Any),
+{
+    let mut deserializer = Deserializer::from_reader(reader);
+    let mut documents = 0;
+    // `end` succeeds only when nothing but whitespace remains
+    while deserializer.end().is_err() {
+        walk(&mut deserializer, options, &mut visit)?;
+        documents += 1;
+    }
+    Ok(documents)
+}
+
+fn walk<'de, R>(
+    deserializer: &mut Deserializer<R>,
+    options: &Options,
+    visit: &mut dyn FnMut(&Breadcrumbs, &dyn Any),
+) -> serde_json::Result<()>
+where
+    R: serde_json::de::Read<'de>,
+{
+    Walker {
+        options,
+        breadcrumbs: Breadcrumbs::new(),
+        visit,
+    }
+    .deserialize(deserializer)
+}
+
+// Visits each value as it is deserialized.
+struct Walker<'a> {
+    options: &'a Options,
+    breadcrumbs: Breadcrumbs,
+    visit: &'a mut dyn FnMut(&Breadcrumbs, &dyn Any),
+}
+
+impl<'a> Walker<'a> {
+    fn scalar<E>(self, value: Value) -> Result<(), E> {
+        value.introspect_from_with(self.options, self.breadcrumbs, self.visit);
+        Ok(())
+    }
+
+    // visit an empty array or object, and return the breadcrumbs of its children
+    fn container(&mut self, value: Value) -> Breadcrumbs {
+        (self.visit)(&self.breadcrumbs, &value);
+        let mut breadcrumbs = self.breadcrumbs.clone();
+        match value {
+            Value::Array(items) => {
+                breadcrumbs.push_back(Breadcrumb::Variant("Array"));
+                (self.visit)(&breadcrumbs, &items);
+            }
+            Value::Object(members) => {
+                breadcrumbs.push_back(Breadcrumb::Variant("Object"));
+                (self.visit)(&breadcrumbs, &members);
+            }
+            _ => unreachable!("only arrays and objects are containers"),
+        }
+        breadcrumbs
+    }
+
+    fn child(&mut self, breadcrumbs: Breadcrumbs) -> Walker<'_> {
+        Walker {
+            options: self.options,
+            breadcrumbs,
+            visit: &mut *self.visit,
+        }
+    }
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for Walker<'a> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, 'a> de::Visitor<'de> for Walker<'a> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any valid JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<(), E> {
+        self.scalar(Value::Null)
+    }
+
+    fn visit_bool<E>(self, b: bool) -> Result<(), E> {
+        self.scalar(Value::Bool(b))
+    }
+
+    fn visit_i64<E>(self, n: i64) -> Result<(), E> {
+        self.scalar(Value::Number(n.into()))
+    }
+
+    fn visit_u64<E>(self, n: u64) -> Result<(), E> {
+        self.scalar(Value::Number(n.into()))
+    }
+
+    fn visit_f64<E>(self, n: f64) -> Result<(), E> {
+        self.scalar(Number::from_f64(n).map_or(Value::Null, Value::Number))
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<(), E> {
+        self.scalar(Value::String(s.to_string()))
+    }
+
+    fn visit_string<E>(self, s: String) -> Result<(), E> {
+        self.scalar(Value::String(s))
+    }
+
+    fn visit_seq<A>(mut self, mut seq: A) -> Result<(), A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let breadcrumbs = self.container(Value::Array(Vec::new()));
+        let mut idx = 0;
+        loop {
+            let mut breadcrumbs = breadcrumbs.clone();
+            breadcrumbs.push_back(Breadcrumb::Index(format!("{}", idx)));
+            if seq.next_element_seed(self.child(breadcrumbs))?.is_none() {
+                return Ok(());
+            }
+            idx += 1;
+        }
+    }
+
+    fn visit_map<A>(mut self, mut map: A) -> Result<(), A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let breadcrumbs = self.container(Value::Object(Map::new()));
+        while let Some(key) = map.next_key::<String>()? {
+            if self.options.map_keys {
+                let mut breadcrumbs = breadcrumbs.clone();
+                breadcrumbs.push_back(Breadcrumb::MapKey(key.clone()));
+                key.introspect_from_with(self.options, breadcrumbs, &mut *self.visit);
+            }
+            let mut breadcrumbs = breadcrumbs.clone();
+            breadcrumbs.push_back(Breadcrumb::Index(key));
+            map.next_value_seed(self.child(breadcrumbs))?;
+        }
+        Ok(())
+    }
+}
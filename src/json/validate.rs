@@ -0,0 +1,572 @@
+//! Validate JSON against a [JSON Schema](https://json-schema.org/) (draft 2020-12).
+//!
+//! A [`Validator`] checks a `serde_json::Value`, or anything which serializes
+//! to one, and reports every [`Violation`] found rather than only the first.
+//! Each violation is located both by the `Breadcrumbs` by which the offending
+//! node would be visited when introspecting the `Value`, and by its
+//! [`JsonPath`], which can be formatted as a JSON Pointer.
+//!
+//! The assertion keywords of the core and validation vocabularies are
+//! supported, along with `$ref`s to locations within the same schema
+//! document. Keywords which depend on regular expressions (`pattern`,
+//! `patternProperties`), on annotation collection (`unevaluatedItems`,
+//! `unevaluatedProperties`), or on other documents are ignored, as is
+//! `format`, which is an annotation by default. As the members which match
+//! `patternProperties` are not known, `additionalProperties` is not checked
+//! in a schema which has `patternProperties`.
+
+use super::{path::Location, JsonPath};
+use crate::Breadcrumbs;
+use serde_json::{Map, Value};
+use std::{fmt, fs::File, io, path::Path};
+
+// how deeply `$ref`s may nest without descending into the instance
+const MAX_REF_DEPTH: usize = 64;
+
+/// A single way in which a value fails to match a schema.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Violation {
+    /// The breadcrumbs by which the offending node is visited, from the root
+    /// `serde_json::Value`.
+    pub breadcrumbs: Breadcrumbs,
+    /// The path to the offending node.
+    pub path: JsonPath,
+    /// The schema keyword which was violated, such as `type`.
+    pub keyword: &'static str,
+    /// A description of the violation.
+    pub message: String,
+}
+
+impl Violation {
+    /// The JSON Pointer to the offending node.
+    pub fn pointer(&self) -> String {
+        self.path.to_pointer()
+    }
+}
+
+/// A violation displays as its path and message: `$.a[0]: expected string, found integer`.
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Checks values against a JSON Schema document.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Validator {
+    schema: Value,
+}
+
+impl Validator {
+    /// Create a validator for the given schema document.
+    pub fn new(schema: Value) -> Validator {
+        Validator { schema }
+    }
+
+    /// Read a schema document from `reader`.
+    pub fn from_reader<R: io::Read>(reader: R) -> serde_json::Result<Validator> {
+        serde_json::from_reader(io::BufReader::new(reader)).map(Validator::new)
+    }
+
+    /// Read a schema document from the file at `path`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Validator> {
+        Ok(Validator::from_reader(File::open(path)?)?)
+    }
+
+    /// The schema document.
+    pub fn schema(&self) -> &Value {
+        &self.schema
+    }
+
+    /// Find every violation of the schema by `value`.
+    pub fn validate(&self, value: &Value) -> Vec<Violation> {
+        let mut context = Context {
+            root: &self.schema,
+            violations: Vec::new(),
+        };
+        context.check(&self.schema, value, &Location::default(), 0);
+        context.violations
+    }
+
+    /// Find every violation of the schema by the JSON serialization of `value`.
+    pub fn validate_serialize<T>(&self, value: &T) -> serde_json::Result<Vec<Violation>>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        Ok(self.validate(&serde_json::to_value(value)?))
+    }
+
+    /// True if `value` does not violate the schema.
+    pub fn is_valid(&self, value: &Value) -> bool {
+        self.validate(value).is_empty()
+    }
+}
+
+struct Context<'s> {
+    root: &'s Value,
+    violations: Vec<Violation>,
+}
+
+impl<'s> Context<'s> {
+    fn violation(&mut self, at: &Location, keyword: &'static str, message: String) {
+        self.violations.push(Violation {
+            breadcrumbs: at.breadcrumbs.clone(),
+            path: at.path.clone(),
+            keyword,
+            message,
+        });
+    }
+
+    // check `value` against `schema` without recording any violations
+    fn matches(&self, schema: &Value, value: &Value, at: &Location, depth: usize) -> bool {
+        let mut context = Context {
+            root: self.root,
+            violations: Vec::new(),
+        };
+        context.check(schema, value, at, depth);
+        context.violations.is_empty()
+    }
+
+    fn check(&mut self, schema: &'s Value, value: &Value, at: &Location, depth: usize) {
+        let schema = match schema {
+            Value::Bool(true) => return,
+            Value::Bool(false) => {
+                return self.violation(at, "false", "no value is permitted".to_string())
+            }
+            Value::Object(schema) => schema,
+            _ => return,
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            match self.resolve(reference) {
+                _ if depth >= MAX_REF_DEPTH => self.violation(
+                    at,
+                    "$ref",
+                    format!("too many nested references at {:?}", reference),
+                ),
+                Some(target) => self.check(target, value, at, depth + 1),
+                None => self.violation(
+                    at,
+                    "$ref",
+                    format!("cannot resolve reference {:?}", reference),
+                ),
+            }
+        }
+
+        self.check_any(schema, value, at, depth);
+        match value {
+            Value::Number(n) => self.check_number(schema, n.as_f64().unwrap_or_default(), at),
+            Value::String(s) => self.check_string(schema, s, at),
+            Value::Array(items) => self.check_array(schema, items, at),
+            Value::Object(members) => self.check_object(schema, members, at),
+            Value::Null | Value::Bool(_) => {}
+        }
+    }
+
+    // resolve a reference to a location within the root schema document
+    fn resolve(&self, reference: &str) -> Option<&'s Value> {
+        let pointer = percent_decode(reference.strip_prefix('#')?)?;
+        self.root.pointer(&pointer)
+    }
+
+    // keywords which apply to values of every type
+    fn check_any(
+        &mut self,
+        schema: &'s Map<String, Value>,
+        value: &Value,
+        at: &Location,
+        depth: usize,
+    ) {
+        if let Some(types) = schema.get("type") {
+            let types: Vec<&str> = match types {
+                Value::String(ty) => vec![ty.as_str()],
+                Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            if !types.is_empty() && !types.iter().any(|ty| is_type(value, ty)) {
+                self.violation(
+                    at,
+                    "type",
+                    format!(
+                        "expected {}, found {}",
+                        types.join(" or "),
+                        type_name(value)
+                    ),
+                );
+            }
+        }
+        if let Some(Value::Array(options)) = schema.get("enum") {
+            if !options.iter().any(|option| json_eq(option, value)) {
+                self.violation(
+                    at,
+                    "enum",
+                    format!("{} is not one of the permitted values", value),
+                );
+            }
+        }
+        if let Some(constant) = schema.get("const") {
+            if !json_eq(constant, value) {
+                self.violation(
+                    at,
+                    "const",
+                    format!("expected {}, found {}", constant, value),
+                );
+            }
+        }
+
+        if let Some(Value::Array(schemas)) = schema.get("allOf") {
+            for schema in schemas {
+                self.check(schema, value, at, depth);
+            }
+        }
+        if let Some(Value::Array(schemas)) = schema.get("anyOf") {
+            if !schemas
+                .iter()
+                .any(|schema| self.matches(schema, value, at, depth))
+            {
+                self.violation(
+                    at,
+                    "anyOf",
+                    "matches none of the schemas in anyOf".to_string(),
+                );
+            }
+        }
+        if let Some(Value::Array(schemas)) = schema.get("oneOf") {
+            let matching = schemas
+                .iter()
+                .filter(|schema| self.matches(schema, value, at, depth))
+                .count();
+            if matching != 1 {
+                self.violation(
+                    at,
+                    "oneOf",
+                    format!(
+                        "matches {} of the schemas in oneOf, not exactly one",
+                        matching
+                    ),
+                );
+            }
+        }
+        if let Some(schema) = schema.get("not") {
+            if self.matches(schema, value, at, depth) {
+                self.violation(at, "not", "matches the schema in not".to_string());
+            }
+        }
+        if let Some(condition) = schema.get("if") {
+            let branch = if self.matches(condition, value, at, depth) {
+                schema.get("then")
+            } else {
+                schema.get("else")
+            };
+            if let Some(branch) = branch {
+                self.check(branch, value, at, depth);
+            }
+        }
+    }
+
+    fn check_number(&mut self, schema: &Map<String, Value>, n: f64, at: &Location) {
+        let bound = |keyword| schema.get(keyword).and_then(Value::as_f64);
+        if let Some(divisor) = bound("multipleOf") {
+            // allow for the imprecision of dividing by a decimal fraction
+            let quotient = n / divisor;
+            if divisor > 0.0 && (quotient - quotient.round()).abs() > 1e-9 {
+                self.violation(
+                    at,
+                    "multipleOf",
+                    format!("{} is not a multiple of {}", n, divisor),
+                );
+            }
+        }
+        if let Some(maximum) = bound("maximum") {
+            if n > maximum {
+                self.violation(at, "maximum", format!("{} is greater than {}", n, maximum));
+            }
+        }
+        if let Some(maximum) = bound("exclusiveMaximum") {
+            if n >= maximum {
+                self.violation(
+                    at,
+                    "exclusiveMaximum",
+                    format!("{} is not less than {}", n, maximum),
+                );
+            }
+        }
+        if let Some(minimum) = bound("minimum") {
+            if n < minimum {
+                self.violation(at, "minimum", format!("{} is less than {}", n, minimum));
+            }
+        }
+        if let Some(minimum) = bound("exclusiveMinimum") {
+            if n <= minimum {
+                self.violation(
+                    at,
+                    "exclusiveMinimum",
+                    format!("{} is not greater than {}", n, minimum),
+                );
+            }
+        }
+    }
+
+    fn check_string(&mut self, schema: &Map<String, Value>, s: &str, at: &Location) {
+        let length = s.chars().count() as u64;
+        if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+            if length > max {
+                self.violation(
+                    at,
+                    "maxLength",
+                    format!("length {} is greater than {}", length, max),
+                );
+            }
+        }
+        if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+            if length < min {
+                self.violation(
+                    at,
+                    "minLength",
+                    format!("length {} is less than {}", length, min),
+                );
+            }
+        }
+    }
+
+    fn check_array(&mut self, schema: &'s Map<String, Value>, items: &[Value], at: &Location) {
+        let len = items.len() as u64;
+        if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+            if len > max {
+                self.violation(
+                    at,
+                    "maxItems",
+                    format!("{} items is more than {}", len, max),
+                );
+            }
+        }
+        if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+            if len < min {
+                self.violation(
+                    at,
+                    "minItems",
+                    format!("{} items is fewer than {}", len, min),
+                );
+            }
+        }
+        if schema.get("uniqueItems") == Some(&Value::Bool(true)) {
+            let duplicate = (0..items.len())
+                .flat_map(|i| (i + 1..items.len()).map(move |j| (i, j)))
+                .find(|&(i, j)| json_eq(&items[i], &items[j]));
+            if let Some((i, j)) = duplicate {
+                self.violation(
+                    at,
+                    "uniqueItems",
+                    format!("items {} and {} are equal", i, j),
+                );
+            }
+        }
+
+        let prefix = match schema.get("prefixItems") {
+            Some(Value::Array(prefix)) => prefix.as_slice(),
+            _ => &[],
+        };
+        for (idx, item) in items.iter().enumerate() {
+            let item_schema = match prefix.get(idx) {
+                Some(schema) => Some(schema),
+                None => schema.get("items"),
+            };
+            if let Some(item_schema) = item_schema {
+                self.check(item_schema, item, &at.item(idx), 0);
+            }
+        }
+
+        if let Some(contains) = schema.get("contains") {
+            let count = items
+                .iter()
+                .enumerate()
+                .filter(|(idx, item)| self.matches(contains, item, &at.item(*idx), 0))
+                .count() as u64;
+            let min = schema
+                .get("minContains")
+                .and_then(Value::as_u64)
+                .unwrap_or(1);
+            if count < min {
+                self.violation(
+                    at,
+                    "contains",
+                    format!(
+                        "{} items match the schema in contains, fewer than {}",
+                        count, min
+                    ),
+                );
+            }
+            if let Some(max) = schema.get("maxContains").and_then(Value::as_u64) {
+                if count > max {
+                    self.violation(
+                        at,
+                        "maxContains",
+                        format!(
+                            "{} items match the schema in contains, more than {}",
+                            count, max
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_object(
+        &mut self,
+        schema: &'s Map<String, Value>,
+        members: &Map<String, Value>,
+        at: &Location,
+    ) {
+        let len = members.len() as u64;
+        if let Some(max) = schema.get("maxProperties").and_then(Value::as_u64) {
+            if len > max {
+                self.violation(
+                    at,
+                    "maxProperties",
+                    format!("{} members is more than {}", len, max),
+                );
+            }
+        }
+        if let Some(min) = schema.get("minProperties").and_then(Value::as_u64) {
+            if len < min {
+                self.violation(
+                    at,
+                    "minProperties",
+                    format!("{} members is fewer than {}", len, min),
+                );
+            }
+        }
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !members.contains_key(key) {
+                    self.violation(at, "required", format!("missing required member {:?}", key));
+                }
+            }
+        }
+        if let Some(Value::Object(dependencies)) = schema.get("dependentRequired") {
+            for (key, required) in dependencies {
+                if !members.contains_key(key) {
+                    continue;
+                }
+                for dependency in required
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                {
+                    if !members.contains_key(dependency) {
+                        self.violation(
+                            at,
+                            "dependentRequired",
+                            format!("member {:?} requires member {:?}", key, dependency),
+                        );
+                    }
+                }
+            }
+        }
+
+        let properties = schema.get("properties").and_then(Value::as_object);
+        // any member may match a pattern, and so not be additional
+        let additional = match schema.get("patternProperties") {
+            Some(_) => None,
+            None => schema.get("additionalProperties"),
+        };
+        for (key, member) in members {
+            let location = at.member(key);
+            if let Some(names) = schema.get("propertyNames") {
+                if !self.matches(names, &Value::String(key.clone()), &location, 0) {
+                    self.violation(
+                        &location,
+                        "propertyNames",
+                        format!(
+                            "member name {:?} does not match the schema in propertyNames",
+                            key
+                        ),
+                    );
+                }
+            }
+            match properties.and_then(|properties| properties.get(key)) {
+                Some(property) => self.check(property, member, &location, 0),
+                None => {
+                    if let Some(additional) = additional {
+                        if additional == &Value::Bool(false) {
+                            self.violation(
+                                &location,
+                                "additionalProperties",
+                                format!("member {:?} is not permitted", key),
+                            );
+                        } else {
+                            self.check(additional, member, &location, 0);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn is_type(value: &Value, ty: &str) -> bool {
+    match (ty, value) {
+        ("null", Value::Null)
+        | ("boolean", Value::Bool(_))
+        | ("number", Value::Number(_))
+        | ("string", Value::String(_))
+        | ("array", Value::Array(_))
+        | ("object", Value::Object(_)) => true,
+        ("integer", Value::Number(n)) => {
+            n.is_i64() || n.is_u64() || n.as_f64().is_some_and(|f| f.fract() == 0.0)
+        }
+        _ => false,
+    }
+}
+
+pub(super) fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+// equality as defined by JSON Schema, in which numbers are equal by value
+pub(super) fn json_eq(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => match (a.as_i64(), b.as_i64()) {
+            (Some(a), Some(b)) => a == b,
+            _ => match (a.as_u64(), b.as_u64()) {
+                (Some(a), Some(b)) => a == b,
+                _ => a.as_f64() == b.as_f64(),
+            },
+        },
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| json_eq(a, b))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, a)| b.get(key).is_some_and(|b| json_eq(a, b)))
+        }
+        (a, b) => a == b,
+    }
+}
+
+// decode the percent-encoded characters of a URI fragment
+fn percent_decode(fragment: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(fragment.len());
+    let mut rest = fragment.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
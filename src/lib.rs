@@ -12,23 +12,294 @@
 //! It also includes the trail of accessors and selectors describing how to get
 //! to the current location from the root object. Given those two things, it is
 //! straightforward to find and access the portion of data of interest.
+//!
+//! ## `serde_json`
+//!
+//! With the `serde-json` feature, `Introspect` is implemented for the
+//! `serde_json` types. Every `serde_json::Value` visits itself, and then its
+//! contents with a trailing `Breadcrumb::Variant` naming the active variant:
+//!
+//! | Value       | Breadcrumb          | Child                                                 |
+//! |-------------|---------------------|-------------------------------------------------------|
+//! | `Null`      | `Variant("Null")`   | `()`                                                  |
+//! | `Bool(_)`   | `Variant("Bool")`   | `bool`                                                |
+//! | `Number(_)` | `Variant("Number")` | `serde_json::Number`                                  |
+//! | `String(_)` | `Variant("String")` | `String`                                              |
+//! | `Array(_)`  | `Variant("Array")`  | `Vec<Value>`; each item follows `Index("0")`, …       |
+//! | `Object(_)` | `Variant("Object")` | `Map<String, Value>`; each value follows `Index(key)` |
+//!
+//! Object keys are not quoted in their `Breadcrumb::Index`, unlike the keys of
+//! `std` maps, which use their `Debug` representation.
+//!
+//! A `serde_json::Number` visits itself and then exactly one primitive child:
+//! a `u64` following `Variant("PosInt")`, an `i64` following `Variant("NegInt")`,
+//! or an `f64` following `Variant("Float")`, depending on how it is stored.
+//!
+//! A `Box<serde_json::value::RawValue>` is a leaf, unless
+//! `Options::parse_raw_json` is set. In that case, its parsed `Value` is
+//! visited following `Variant("Parsed")`.
+//!
+//! A `serde_json::Error` visits its `serde_json::error::Category`,
+//! `line` and `column` as fields.
+//!
+//! ## `serde`
+//!
+//! With the `serde` feature, the [`serialize`] module introspects any type
+//! which implements `serde::Serialize`, without deriving `Introspect` for it.
 
 #[cfg(feature = "derive")]
-pub use spectacle_derive::Spectacle;
+pub use spectacle_derive::{Navigate, Spectacle};
 use spectacle_impl_tuples::impl_tuples;
 use std::any::Any;
+use std::fmt;
+
+pub mod diff;
+pub mod dom;
+pub mod flatten;
+pub mod graph;
+#[cfg(feature = "serde-json")]
+pub mod json;
+pub mod overrides;
+pub mod path;
+pub mod pretty;
+#[cfg(feature = "serde")]
+pub mod serialize;
+pub mod type_info;
+
+pub use type_info::TypeInfo;
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub enum Breadcrumb {
     Variant(&'static str),
     Field(&'static str),
     Index(String),
     TupleIndex(usize),
-    SetMember,
+    /// A member of a set.
+    ///
+    /// Ordered sets and heaps identify members by their ordinal in iteration
+    /// order. Hash sets identify members by their stable hash, as 16 hex
+    /// digits. Where the hashes of several members collide, all but the
+    /// first have a suffix `#1`, `#2`, and so on, ordered by a second hash,
+    /// so that each member of a set has a distinct `SetMember`.
+    SetMember(String),
+    /// The key of a map entry, visited only when `Options::map_keys` is set.
+    ///
+    /// The contained string is identical to the `Index` of the corresponding value.
+    MapKey(String),
+}
+
+/// Breadcrumbs display as they would appear within a path: `::Variant`,
+/// `.field`, `[index]`, `.0`, `{member}` and `<key>`.
+impl fmt::Display for Breadcrumb {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Breadcrumb::Variant(variant) => write!(f, "::{}", variant),
+            Breadcrumb::Field(field) => write!(f, ".{}", field),
+            Breadcrumb::Index(index) => write!(f, "[{}]", index),
+            Breadcrumb::TupleIndex(index) => write!(f, ".{}", index),
+            Breadcrumb::SetMember(member) => write!(f, "{{{}}}", member),
+            Breadcrumb::MapKey(key) => write!(f, "<{}>", key),
+        }
+    }
 }
 
 pub type Breadcrumbs = im::vector::Vector<Breadcrumb>;
 
+/// Format a trail of breadcrumbs as a path from the root, such as
+/// `$.foo[0]::Some`. The empty trail is `$`.
+pub fn format_path(breadcrumbs: &Breadcrumbs) -> String {
+    let mut path = String::from("$");
+    for breadcrumb in breadcrumbs {
+        path.push_str(&breadcrumb.to_string());
+    }
+    path
+}
+
+/// Options controlling the traversal performed by
+/// [`Introspect::introspect_with`].
+///
+/// The default options produce exactly the same traversal as
+/// [`Introspect::introspect`].
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Options {
+    /// Visit map keys as well as map values.
+    ///
+    /// When set, each key of a map is recursively introspected immediately
+    /// before its value, with a trailing `Breadcrumb::MapKey`.
+    pub map_keys: bool,
+
+    /// Visit the members of hash-based collections in a stable order.
+    ///
+    /// By default, `HashMap` and `HashSet` are visited in whatever order they
+    /// iterate, which varies from run to run. When set, map entries are
+    /// visited sorted by their keys: by `Ord` for keys of std types such as
+    /// integers and strings, and otherwise by their `Breadcrumb::Index`. Set
+    /// members are visited sorted by their `Breadcrumb::SetMember`, which is
+    /// a hash of the member by a fixed algorithm. This costs an allocation
+    /// and a sort per collection.
+    pub stable_order: bool,
+
+    /// Parse each `Box<serde_json::value::RawValue>` and visit the result.
+    ///
+    /// When set, raw values which contain valid JSON have the parsed
+    /// `serde_json::Value` visited as a child, with a trailing
+    /// `Breadcrumb::Variant("Parsed")`. This has no effect without the
+    /// `serde-json` feature.
+    pub parse_raw_json: bool,
+}
+
+// Hash `t` with FNV-1a from the given offset basis. The algorithm is fixed
+// and integers are hashed as little-endian bytes, so that the result is
+// consistent between runs, builds and platforms.
+#[cfg(feature = "collections")]
+fn fnv_hash<T: std::hash::Hash + ?Sized>(t: &T, basis: u64) -> u64 {
+    use std::hash::Hasher;
+
+    struct Fnv(u64);
+
+    impl Hasher for Fnv {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for &byte in bytes {
+                self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3);
+            }
+        }
+
+        fn write_u16(&mut self, n: u16) {
+            self.write(&n.to_le_bytes());
+        }
+
+        fn write_u32(&mut self, n: u32) {
+            self.write(&n.to_le_bytes());
+        }
+
+        fn write_u64(&mut self, n: u64) {
+            self.write(&n.to_le_bytes());
+        }
+
+        fn write_u128(&mut self, n: u128) {
+            self.write(&n.to_le_bytes());
+        }
+
+        fn write_usize(&mut self, n: usize) {
+            self.write_u64(n as u64);
+        }
+
+        fn write_i16(&mut self, n: i16) {
+            self.write_u16(n as u16);
+        }
+
+        fn write_i32(&mut self, n: i32) {
+            self.write_u32(n as u32);
+        }
+
+        fn write_i64(&mut self, n: i64) {
+            self.write_u64(n as u64);
+        }
+
+        fn write_i128(&mut self, n: i128) {
+            self.write_u128(n as u128);
+        }
+
+        fn write_isize(&mut self, n: isize) {
+            self.write_u64(n as u64);
+        }
+    }
+
+    let mut hasher = Fnv(basis);
+    t.hash(&mut hasher);
+    hasher.finish()
+}
+
+// Hash `t` so that the result is consistent between runs.
+#[cfg(feature = "collections")]
+fn stable_hash<T: std::hash::Hash + ?Sized>(t: &T) -> u64 {
+    fnv_hash(t, 0xcbf2_9ce4_8422_2325)
+}
+
+// A second stable hash of `t`, independent of `stable_hash`, which orders
+// values whose stable hashes collide.
+#[cfg(feature = "collections")]
+fn tiebreak_hash<T: std::hash::Hash + ?Sized>(t: &T) -> u64 {
+    fnv_hash(t, 0x6c62_272e_07bb_0142)
+}
+
+// The members of a hash set, each with its `Breadcrumb::SetMember`, in
+// iteration order or sorted by it.
+//
+// Each member is identified by its stable hash. Where the stable hashes of
+// several members collide, all but the first, in order of their tiebreak
+// hashes, have a suffix `#1`, `#2`, and so on.
+#[cfg(feature = "collections")]
+pub(crate) fn hash_set_members<T: std::hash::Hash>(
+    set: &std::collections::HashSet<T>,
+    sorted: bool,
+) -> Vec<(String, &T)> {
+    let members: Vec<_> = set.iter().map(|item| (stable_hash(item), item)).collect();
+    if !sorted {
+        let mut hashes = std::collections::HashSet::with_capacity(members.len());
+        if members.iter().all(|(hash, _)| hashes.insert(*hash)) {
+            return members
+                .into_iter()
+                .map(|(hash, item)| (format!("{:016x}", hash), item))
+                .collect();
+        }
+    }
+
+    let mut order: Vec<usize> = (0..members.len()).collect();
+    order.sort_by_cached_key(|&idx| (members[idx].0, tiebreak_hash(members[idx].1)));
+    let mut labels = vec![String::new(); members.len()];
+    let mut collisions = 0;
+    for (position, &idx) in order.iter().enumerate() {
+        let hash = members[idx].0;
+        if position > 0 && members[order[position - 1]].0 == hash {
+            collisions += 1;
+            labels[idx] = format!("{:016x}#{}", hash, collisions);
+        } else {
+            collisions = 0;
+            labels[idx] = format!("{:016x}", hash);
+        }
+    }
+    if sorted {
+        order
+            .into_iter()
+            .map(|idx| (std::mem::take(&mut labels[idx]), members[idx].1))
+            .collect()
+    } else {
+        labels
+            .into_iter()
+            .zip(members.into_iter().map(|(_, item)| item))
+            .collect()
+    }
+}
+
+// Compare two keys of the same type by `Ord`, if it is a type from std which
+// implements it.
+#[cfg(feature = "collections")]
+fn compare_keys(a: &dyn Any, b: &dyn Any) -> Option<std::cmp::Ordering> {
+    macro_rules! compare {
+        ($($t:ty),*) => {
+            $(
+                if let (Some(a), Some(b)) = (a.downcast_ref::<$t>(), b.downcast_ref::<$t>()) {
+                    return Some(a.cmp(b));
+                }
+            )*
+        };
+    }
+
+    compare!(bool, char, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+    compare!(
+        String,
+        &'static str,
+        std::path::PathBuf,
+        std::time::Duration
+    );
+    None
+}
+
 /// Recursively introspect through `Self`.
 ///
 /// Visit each struct field, enum variant, etc. It operates via the
@@ -53,6 +324,18 @@ pub trait Introspect {
         self.introspect_from(Breadcrumbs::new(), visit);
     }
 
+    /// Recursively descend through `Self` according to `options`, visiting it,
+    /// and then all child items.
+    ///
+    /// This is a helper function which just calls `introspect_from_with` with an
+    /// empty `Breadcrumbs` trail.
+    fn introspect_with<F>(&self, options: &Options, visit: F)
+    where
+        F: FnMut(&Breadcrumbs, &dyn Any),
+    {
+        self.introspect_from_with(options, Breadcrumbs::new(), visit);
+    }
+
     /// Recursively descend through `Self`, visiting it, and then all child items.
     ///
     /// The visitor receives two type parameters: a trail of breadcrumbs
@@ -66,6 +349,21 @@ pub trait Introspect {
     fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, visit: F)
     where
         F: FnMut(&Breadcrumbs, &dyn Any);
+
+    /// Recursively descend through `Self` according to `options`, visiting it,
+    /// and then all child items.
+    ///
+    /// The default implementation ignores `options` and calls `introspect_from`,
+    /// which is appropriate for leaf types. Types with children should
+    /// implement this method, pass `options` along to each child, and
+    /// implement `introspect_from` by calling this with `Options::default()`.
+    fn introspect_from_with<F>(&self, options: &Options, breadcrumbs: Breadcrumbs, visit: F)
+    where
+        F: FnMut(&Breadcrumbs, &dyn Any),
+    {
+        let _ = options;
+        self.introspect_from(breadcrumbs, visit);
+    }
 }
 
 impl<T> Introspect for &T
@@ -78,13 +376,27 @@ where
     {
         Introspect::introspect_from(*self, breadcrumbs, visit)
     }
+
+    fn introspect_from_with<F>(&self, options: &Options, breadcrumbs: Breadcrumbs, visit: F)
+    where
+        F: FnMut(&Breadcrumbs, &dyn Any),
+    {
+        Introspect::introspect_from_with(*self, options, breadcrumbs, visit)
+    }
 }
 
 impl<T> Introspect for &'static [T]
 where
     T: Introspect,
 {
-    fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, mut visit: F)
+    fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, visit: F)
+    where
+        F: FnMut(&Breadcrumbs, &dyn Any),
+    {
+        self.introspect_from_with(&Options::default(), breadcrumbs, visit);
+    }
+
+    fn introspect_from_with<F>(&self, options: &Options, breadcrumbs: Breadcrumbs, mut visit: F)
     where
         F: FnMut(&Breadcrumbs, &dyn Any),
     {
@@ -92,7 +404,7 @@ where
         for (idx, child) in self.iter().enumerate() {
             let mut breadcrumbs = breadcrumbs.clone();
             breadcrumbs.push_back(Breadcrumb::Index(format!("{}", idx)));
-            child.introspect_from(breadcrumbs, &mut visit);
+            child.introspect_from_with(options, breadcrumbs, &mut visit);
         }
     }
 }
@@ -142,7 +454,14 @@ macro_rules! impl_array {
         where
             T: 'static + Introspect,
         {
-            fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, mut visit: F)
+            fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, visit: F)
+            where
+                F: FnMut(&Breadcrumbs, &dyn Any),
+            {
+                self.introspect_from_with(&Options::default(), breadcrumbs, visit);
+            }
+
+            fn introspect_from_with<F>(&self, options: &Options, breadcrumbs: Breadcrumbs, mut visit: F)
             where
                 F: FnMut(&Breadcrumbs, &dyn Any),
             {
@@ -150,7 +469,7 @@ macro_rules! impl_array {
                 for (idx, child) in self.iter().enumerate() {
                     let mut breadcrumbs = breadcrumbs.clone();
                     breadcrumbs.push_back(Breadcrumb::Index(format!("{}", idx)));
-                    child.introspect_from(breadcrumbs, &mut visit);
+                    child.introspect_from_with(options, breadcrumbs, &mut visit);
                 }
             }
         }
@@ -173,7 +492,14 @@ impl<T> Introspect for Option<T>
 where
     T: 'static + Introspect,
 {
-    fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, mut visit: F)
+    fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, visit: F)
+    where
+        F: FnMut(&Breadcrumbs, &dyn Any),
+    {
+        self.introspect_from_with(&Options::default(), breadcrumbs, visit);
+    }
+
+    fn introspect_from_with<F>(&self, options: &Options, breadcrumbs: Breadcrumbs, mut visit: F)
     where
         F: FnMut(&Breadcrumbs, &dyn Any),
     {
@@ -181,7 +507,7 @@ where
         if let Some(t) = self {
             let mut breadcrumbs = breadcrumbs.clone();
             breadcrumbs.push_back(Breadcrumb::Variant("Some"));
-            t.introspect_from(breadcrumbs, &mut visit);
+            t.introspect_from_with(options, breadcrumbs, &mut visit);
         }
     }
 }
@@ -191,7 +517,14 @@ where
     T: 'static + Introspect,
     E: 'static + Introspect,
 {
-    fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, mut visit: F)
+    fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, visit: F)
+    where
+        F: FnMut(&Breadcrumbs, &dyn Any),
+    {
+        self.introspect_from_with(&Options::default(), breadcrumbs, visit);
+    }
+
+    fn introspect_from_with<F>(&self, options: &Options, breadcrumbs: Breadcrumbs, mut visit: F)
     where
         F: FnMut(&Breadcrumbs, &dyn Any),
     {
@@ -200,12 +533,12 @@ where
             Ok(t) => {
                 let mut breadcrumbs = breadcrumbs.clone();
                 breadcrumbs.push_back(Breadcrumb::Variant("Ok"));
-                t.introspect_from(breadcrumbs, &mut visit);
+                t.introspect_from_with(options, breadcrumbs, &mut visit);
             }
             Err(e) => {
                 let mut breadcrumbs = breadcrumbs.clone();
                 breadcrumbs.push_back(Breadcrumb::Variant("Err"));
-                e.introspect_from(breadcrumbs, &mut visit);
+                e.introspect_from_with(options, breadcrumbs, &mut visit);
             }
         }
     }
@@ -218,7 +551,14 @@ macro_rules! impl_list {
         where
             T: 'static + Introspect,
         {
-            fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, mut visit: F)
+            fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, visit: F)
+            where
+                F: FnMut(&Breadcrumbs, &dyn Any),
+            {
+                self.introspect_from_with(&Options::default(), breadcrumbs, visit);
+            }
+
+            fn introspect_from_with<F>(&self, options: &Options, breadcrumbs: Breadcrumbs, mut visit: F)
             where
                 F: FnMut(&Breadcrumbs, &dyn Any),
             {
@@ -226,7 +566,7 @@ macro_rules! impl_list {
                 for (idx, item) in self.iter().enumerate() {
                     let mut breadcrumbs = breadcrumbs.clone();
                     breadcrumbs.push_back(Breadcrumb::Index(format!("{}", idx)));
-                    item.introspect_from(breadcrumbs, &mut visit);
+                    item.introspect_from_with(options, breadcrumbs, &mut visit);
                 }
             }
         }
@@ -246,55 +586,114 @@ macro_rules! impl_set {
         where
             T: 'static + Introspect,
         {
-            fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, mut visit: F)
+            fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, visit: F)
+            where
+                F: FnMut(&Breadcrumbs, &dyn Any),
+            {
+                self.introspect_from_with(&Options::default(), breadcrumbs, visit);
+            }
+
+            fn introspect_from_with<F>(&self, options: &Options, breadcrumbs: Breadcrumbs, mut visit: F)
             where
                 F: FnMut(&Breadcrumbs, &dyn Any),
             {
                 visit(&breadcrumbs, self);
-                for item in self.iter() {
+                for (idx, item) in self.iter().enumerate() {
                     let mut breadcrumbs = breadcrumbs.clone();
-                    breadcrumbs.push_back(Breadcrumb::SetMember);
-                    item.introspect_from(breadcrumbs, &mut visit);
+                    breadcrumbs.push_back(Breadcrumb::SetMember(format!("{}", idx)));
+                    item.introspect_from_with(options, breadcrumbs, &mut visit);
                 }
             }
         }
     };
 }
 
-impl_set!(std::collections::HashSet);
 impl_set!(std::collections::BTreeSet);
 impl_set!(std::collections::BinaryHeap);
 
+#[cfg(feature = "collections")]
+impl<T> Introspect for std::collections::HashSet<T>
+where
+    T: 'static + std::hash::Hash + Introspect,
+{
+    fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, visit: F)
+    where
+        F: FnMut(&Breadcrumbs, &dyn Any),
+    {
+        self.introspect_from_with(&Options::default(), breadcrumbs, visit);
+    }
+
+    fn introspect_from_with<F>(&self, options: &Options, breadcrumbs: Breadcrumbs, mut visit: F)
+    where
+        F: FnMut(&Breadcrumbs, &dyn Any),
+    {
+        visit(&breadcrumbs, self);
+        for (member, item) in hash_set_members(self, options.stable_order) {
+            let mut breadcrumbs = breadcrumbs.clone();
+            breadcrumbs.push_back(Breadcrumb::SetMember(member));
+            item.introspect_from_with(options, breadcrumbs, &mut visit);
+        }
+    }
+}
+
 macro_rules! impl_map {
-    ($($t:ident)::+) => {
+    ($($t:ident)::+, unordered = $unordered:expr) => {
         #[cfg(feature = "collections")]
         impl<K, V> Introspect for $($t)::+<K, V>
         where
-            K: 'static + std::fmt::Debug,
+            K: 'static + std::fmt::Debug + Introspect,
             V: 'static + Introspect,
         {
-            fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, mut visit: F)
+            fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, visit: F)
+            where
+                F: FnMut(&Breadcrumbs, &dyn Any),
+            {
+                self.introspect_from_with(&Options::default(), breadcrumbs, visit);
+            }
+
+            fn introspect_from_with<F>(&self, options: &Options, breadcrumbs: Breadcrumbs, mut visit: F)
             where
                 F: FnMut(&Breadcrumbs, &dyn Any),
             {
                 visit(&breadcrumbs, self);
-                for (k, v) in self.iter() {
+                let mut visit_entry = |key: String, k: &K, v: &V| {
+                    if options.map_keys {
+                        let mut breadcrumbs = breadcrumbs.clone();
+                        breadcrumbs.push_back(Breadcrumb::MapKey(key.clone()));
+                        k.introspect_from_with(options, breadcrumbs, &mut visit);
+                    }
                     let mut breadcrumbs = breadcrumbs.clone();
-                    breadcrumbs.push_back(Breadcrumb::Index(format!("{:?}", k)));
-                    v.introspect_from(breadcrumbs, &mut visit);
+                    breadcrumbs.push_back(Breadcrumb::Index(key));
+                    v.introspect_from_with(options, breadcrumbs, &mut visit);
+                };
+                if $unordered && options.stable_order {
+                    let mut entries: Vec<_> = self
+                        .iter()
+                        .map(|(k, v)| (format!("{:?}", k), k, v))
+                        .collect();
+                    entries.sort_by(|(a, ka, _), (b, kb, _)| {
+                        compare_keys(*ka, *kb).unwrap_or_else(|| a.cmp(b))
+                    });
+                    for (key, k, v) in entries {
+                        visit_entry(key, k, v);
+                    }
+                } else {
+                    for (k, v) in self.iter() {
+                        visit_entry(format!("{:?}", k), k, v);
+                    }
                 }
             }
         }
     };
 }
 
-impl_map!(std::collections::HashMap);
-impl_map!(std::collections::BTreeMap);
+impl_map!(std::collections::HashMap, unordered = true);
+impl_map!(std::collections::BTreeMap, unordered = false);
 
 macro_rules! impl_serde_json {
-    ($($t:ident)::+) => {
+    ($t:ty) => {
         #[cfg(feature = "serde-json")]
-        impl Introspect for $($t)::+ {
+        impl Introspect for $t {
             fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, mut visit: F)
             where
                 F: FnMut(&Breadcrumbs, &dyn Any),
@@ -304,65 +703,161 @@ macro_rules! impl_serde_json {
         }
     };
 
-    ($t:ident, $($ts:ident),+ $(,)?) => {
-        impl_primitive!($t);
-        impl_primitive!($($ts),*);
+    ($t:ty, $($ts:ty),+ $(,)?) => {
+        impl_serde_json!($t);
+        impl_serde_json!($($ts),*);
     };
 }
 
-impl_serde_json!(serde_json::Error);
-impl_serde_json!(serde_json::Number);
+impl_serde_json!(serde_json::error::Category);
 
 #[cfg(feature = "serde-json")]
-impl Introspect for serde_json::Map<String, serde_json::Value> {
+impl Introspect for serde_json::Error {
     fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, mut visit: F)
+    where
+        F: FnMut(&Breadcrumbs, &dyn Any),
+    {
+        visit(&breadcrumbs, self);
+        {
+            let mut breadcrumbs = breadcrumbs.clone();
+            breadcrumbs.push_back(Breadcrumb::Field("category"));
+            self.classify().introspect_from(breadcrumbs, &mut visit);
+        }
+        {
+            let mut breadcrumbs = breadcrumbs.clone();
+            breadcrumbs.push_back(Breadcrumb::Field("line"));
+            self.line().introspect_from(breadcrumbs, &mut visit);
+        }
+        {
+            let mut breadcrumbs = breadcrumbs.clone();
+            breadcrumbs.push_back(Breadcrumb::Field("column"));
+            self.column().introspect_from(breadcrumbs, &mut visit);
+        }
+    }
+}
+
+#[cfg(feature = "serde-json")]
+impl Introspect for serde_json::Number {
+    fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, mut visit: F)
+    where
+        F: FnMut(&Breadcrumbs, &dyn Any),
+    {
+        visit(&breadcrumbs, self);
+        let mut breadcrumbs = breadcrumbs.clone();
+        if let Some(n) = self.as_u64() {
+            breadcrumbs.push_back(Breadcrumb::Variant("PosInt"));
+            n.introspect_from(breadcrumbs, &mut visit);
+        } else if let Some(n) = self.as_i64() {
+            breadcrumbs.push_back(Breadcrumb::Variant("NegInt"));
+            n.introspect_from(breadcrumbs, &mut visit);
+        } else if let Some(n) = self.as_f64() {
+            breadcrumbs.push_back(Breadcrumb::Variant("Float"));
+            n.introspect_from(breadcrumbs, &mut visit);
+        }
+    }
+}
+
+#[cfg(feature = "serde-json")]
+impl Introspect for Box<serde_json::value::RawValue> {
+    fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, visit: F)
+    where
+        F: FnMut(&Breadcrumbs, &dyn Any),
+    {
+        self.introspect_from_with(&Options::default(), breadcrumbs, visit);
+    }
+
+    fn introspect_from_with<F>(&self, options: &Options, breadcrumbs: Breadcrumbs, mut visit: F)
+    where
+        F: FnMut(&Breadcrumbs, &dyn Any),
+    {
+        visit(&breadcrumbs, self);
+        if options.parse_raw_json {
+            if let Ok(value) = serde_json::from_str::<serde_json::Value>(self.get()) {
+                let mut breadcrumbs = breadcrumbs.clone();
+                breadcrumbs.push_back(Breadcrumb::Variant("Parsed"));
+                value.introspect_from_with(options, breadcrumbs, &mut visit);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde-json")]
+impl Introspect for serde_json::Map<String, serde_json::Value> {
+    fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, visit: F)
+    where
+        F: FnMut(&Breadcrumbs, &dyn Any),
+    {
+        self.introspect_from_with(&Options::default(), breadcrumbs, visit);
+    }
+
+    fn introspect_from_with<F>(&self, options: &Options, breadcrumbs: Breadcrumbs, mut visit: F)
     where
         F: FnMut(&Breadcrumbs, &dyn Any),
     {
         visit(&breadcrumbs, self);
         for (k, v) in self.iter() {
+            if options.map_keys {
+                let mut breadcrumbs = breadcrumbs.clone();
+                breadcrumbs.push_back(Breadcrumb::MapKey(k.to_string()));
+                k.introspect_from_with(options, breadcrumbs, &mut visit);
+            }
             let mut breadcrumbs = breadcrumbs.clone();
-            breadcrumbs.push_back(Breadcrumb::Index(format!("{}", k)));
-            v.introspect_from(breadcrumbs, &mut visit);
+            breadcrumbs.push_back(Breadcrumb::Index(k.to_string()));
+            v.introspect_from_with(options, breadcrumbs, &mut visit);
         }
     }
 }
 
 #[cfg(feature = "serde-json")]
 impl Introspect for serde_json::Value {
-    fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, mut visit: F)
+    fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, visit: F)
     where
         F: FnMut(&Breadcrumbs, &dyn Any),
     {
+        self.introspect_from_with(&Options::default(), breadcrumbs, visit);
+    }
+
+    fn introspect_from_with<F>(&self, options: &Options, breadcrumbs: Breadcrumbs, mut visit: F)
+    where
+        F: FnMut(&Breadcrumbs, &dyn Any),
+    {
+        // `Value` is recursive. Erasing the visitor's type here means that
+        // each level of nesting doesn't instantiate this function with a new
+        // `&mut F`, which would never terminate.
+        let visit: &mut dyn FnMut(&Breadcrumbs, &dyn Any) = &mut visit;
         visit(&breadcrumbs, self);
 
         match self {
+            serde_json::Value::Null => {
+                let mut breadcrumbs = breadcrumbs.clone();
+                breadcrumbs.push_back(Breadcrumb::Variant("Null"));
+                ().introspect_from_with(options, breadcrumbs, &mut *visit);
+            }
             serde_json::Value::Bool(x) => {
                 let mut breadcrumbs = breadcrumbs.clone();
                 breadcrumbs.push_back(Breadcrumb::Variant("Bool"));
-                x.introspect_from(breadcrumbs, &mut visit);
+                x.introspect_from_with(options, breadcrumbs, &mut *visit);
             }
             serde_json::Value::Number(x) => {
                 let mut breadcrumbs = breadcrumbs.clone();
                 breadcrumbs.push_back(Breadcrumb::Variant("Number"));
-                x.introspect_from(breadcrumbs, &mut visit);
+                x.introspect_from_with(options, breadcrumbs, &mut *visit);
             }
             serde_json::Value::String(x) => {
                 let mut breadcrumbs = breadcrumbs.clone();
                 breadcrumbs.push_back(Breadcrumb::Variant("String"));
-                x.introspect_from(breadcrumbs, &mut visit);
+                x.introspect_from_with(options, breadcrumbs, &mut *visit);
             }
             serde_json::Value::Array(x) => {
                 let mut breadcrumbs = breadcrumbs.clone();
                 breadcrumbs.push_back(Breadcrumb::Variant("Array"));
-                x.introspect_from(breadcrumbs, &mut visit);
+                x.introspect_from_with(options, breadcrumbs, &mut *visit);
             }
             serde_json::Value::Object(x) => {
                 let mut breadcrumbs = breadcrumbs.clone();
                 breadcrumbs.push_back(Breadcrumb::Variant("Object"));
-                x.introspect_from(breadcrumbs, &mut visit);
+                x.introspect_from_with(options, breadcrumbs, &mut *visit);
             }
-            _ => {}
         }
     }
 }
@@ -0,0 +1,529 @@
+//! Override the values within a typed configuration by dotted keys, as given by
+//! environment variables or command line arguments.
+//!
+//! An [`Override`] pairs a key, such as `database.pool.max`, with the text of
+//! a value, such as `10`. Overrides can be parsed from `key=value` strings,
+//! collected from `--set key=value` arguments by [`from_args`], or collected
+//! from environment variables such as `APP__DATABASE__POOL__MAX=10` by
+//! [`from_env`].
+//!
+//! With [`apply`], each key is resolved against the values visited by
+//! introspecting the configuration. Its segments name struct fields, ignoring
+//! ASCII case, tuple and list indices, map keys, and the variants of `Result`s,
+//! while the active variant of an enum is named along with its field. The only
+//! value beneath an enum variant, such as the contents of a `Some` or of a
+//! JSON object, is passed through without a segment of its own. The text of
+//! the value is then parsed according to the type of the value found there:
+//!
+//! - booleans, characters, integers and floats are parsed by `FromStr`, with
+//!   booleans ignoring ASCII case;
+//! - strings are taken as they are;
+//! - with the `serde-json` feature, JSON values are parsed as JSON, falling
+//!   back to a string;
+//! - the contents of a `Some` are given by their text, and an `Option` is set
+//!   to `None` by empty text;
+//! - any other value is taken to be an enum, given by the exact name of a
+//!   variant without fields.
+//!
+//! As only the values present can be found, a `None` cannot be given
+//! contents, nor can an inactive variant be named. [`apply_typed`] instead
+//! resolves each key against the [`TypeInfo`] of the configuration type, and
+//! parses the text according to the type found there, so that enum variants
+//! are named ignoring ASCII case, and a `None` is given contents by their
+//! text.
+//!
+//! The parsed value is then applied with the [`path`](crate::path) module,
+//! so list items and map entries must already exist to be overridden.
+
+use crate::{
+    dom::{known_type, to_node, Node},
+    path::{self, Navigate, PathError, PathErrorKind},
+    type_info::{Fields, Primitive, Type, TypeKind},
+    Breadcrumb, Breadcrumbs, Introspect, TypeInfo,
+};
+use std::{any::Any, fmt, str::FromStr};
+
+/// A value to set at a dotted key.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Override {
+    /// The segments of the key, such as `["database", "pool", "max"]`.
+    pub key: Vec<String>,
+    /// The text of the value.
+    pub value: String,
+}
+
+impl Override {
+    /// The key, with its segments joined by dots.
+    pub fn dotted_key(&self) -> String {
+        self.key.join(".")
+    }
+
+    // an error about this override
+    fn error(&self, kind: OverrideErrorKind) -> OverrideError {
+        OverrideError {
+            key: self.dotted_key(),
+            kind,
+        }
+    }
+}
+
+/// Parse an override from `key=value`, where the key's segments are
+/// separated by dots.
+impl FromStr for Override {
+    type Err = OverrideError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let syntax = || OverrideError {
+            key: s.to_string(),
+            kind: OverrideErrorKind::Syntax,
+        };
+        let (key, value) = s.split_once('=').ok_or_else(syntax)?;
+        let key: Vec<String> = key.trim().split('.').map(str::to_string).collect();
+        if key.iter().any(String::is_empty) {
+            return Err(syntax());
+        }
+        Ok(Override {
+            key,
+            value: value.to_string(),
+        })
+    }
+}
+
+/// Why an override could not be parsed or applied.
+#[derive(Clone, PartialEq, Debug)]
+pub enum OverrideErrorKind {
+    /// The override is not of the form `key=value`.
+    Syntax,
+    /// This segment of the key does not name a field, variant, index or map
+    /// key of the value reached by the preceding segments.
+    UnknownKey { segment: String },
+    /// The key leads to a value which is neither a leaf nor an enum, so cannot
+    /// be parsed from text.
+    Unsupported { type_name: &'static str },
+    /// The text cannot be parsed as a value of the expected type.
+    InvalidValue {
+        expected: &'static str,
+        value: String,
+    },
+    /// The parsed value could not be applied at the path of the key.
+    Path(Box<PathError>),
+}
+
+/// An override which could not be parsed or applied.
+#[derive(Clone, PartialEq, Debug)]
+pub struct OverrideError {
+    /// The dotted key of the override.
+    pub key: String,
+    pub kind: OverrideErrorKind,
+}
+
+/// An override error displays as the key and what went wrong:
+/// `database.pool.max: "ten" is not a valid u32`.
+impl fmt::Display for OverrideError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: ", self.key)?;
+        match &self.kind {
+            OverrideErrorKind::Syntax => write!(f, "expected an override of the form key=value"),
+            OverrideErrorKind::UnknownKey { segment } => {
+                write!(f, "{} does not lead to a value", segment)
+            }
+            OverrideErrorKind::Unsupported { type_name } => {
+                write!(
+                    f,
+                    "a value of type {} cannot be parsed from text",
+                    type_name
+                )
+            }
+            OverrideErrorKind::InvalidValue { expected, value } => {
+                write!(f, "{:?} is not a valid {}", value, expected)
+            }
+            OverrideErrorKind::Path(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for OverrideError {}
+
+/// Collect the overrides given by command line arguments, as `--set key=value`
+/// or `--set=key=value`. Other arguments are ignored.
+pub fn from_args<I, S>(args: I) -> Result<Vec<Override>, OverrideError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut overrides = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let arg = arg.as_ref();
+        if arg == "--set" {
+            let value = args.next().ok_or_else(|| OverrideError {
+                key: arg.to_string(),
+                kind: OverrideErrorKind::Syntax,
+            })?;
+            overrides.push(value.as_ref().parse()?);
+        } else if let Some(value) = arg.strip_prefix("--set=") {
+            overrides.push(value.parse()?);
+        }
+    }
+    Ok(overrides)
+}
+
+/// Collect the overrides given by environment variables named with `prefix`,
+/// such as `APP__DATABASE__POOL__MAX` for the prefix `APP`.
+///
+/// The segments of the key follow the prefix, separated by double
+/// underscores, and are lowercased.
+pub fn from_env(prefix: &str) -> Vec<Override> {
+    from_env_vars(prefix, std::env::vars())
+}
+
+/// Collect the overrides given by the `(name, value)` pairs of environment
+/// variables named with `prefix`, as by [`from_env`].
+pub fn from_env_vars<I>(prefix: &str, vars: I) -> Vec<Override>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let prefix = format!("{}__", prefix);
+    let mut overrides: Vec<Override> = vars
+        .into_iter()
+        .filter_map(|(name, value)| {
+            let key: Vec<String> = name
+                .strip_prefix(&prefix)?
+                .split("__")
+                .map(str::to_lowercase)
+                .collect();
+            if key.iter().any(String::is_empty) {
+                return None;
+            }
+            Some(Override { key, value })
+        })
+        .collect();
+    // the environment is unordered, so apply overrides in a consistent order
+    overrides.sort_by(|a, b| a.key.cmp(&b.key));
+    overrides
+}
+
+/// Apply each of `overrides` to `root` in turn, stopping at the first which
+/// cannot be applied.
+pub fn apply<T: Introspect + Navigate>(
+    root: &mut T,
+    overrides: &[Override],
+) -> Result<(), OverrideError> {
+    for item in overrides {
+        apply_one(root, item)?;
+    }
+    Ok(())
+}
+
+/// Apply `item` to `root`, resolving its key against the values visited by
+/// introspecting `root`.
+pub fn apply_one<T: Introspect + Navigate>(
+    root: &mut T,
+    item: &Override,
+) -> Result<(), OverrideError> {
+    let tree = to_node(&*root).ok_or_else(|| {
+        item.error(OverrideErrorKind::UnknownKey {
+            segment: item.dotted_key(),
+        })
+    })?;
+    let mut node = find(&tree, &item.key).map_err(|kind| item.error(kind))?;
+    let text = item.value.as_str();
+    if !text.is_empty() && node.type_name.is_none() {
+        // the contents of a `Some` are given by their text
+        if let Some(child) = only_variant(node) {
+            node = child;
+        }
+    }
+    let breadcrumbs = &node.breadcrumbs;
+    let ty = path::get(&*root, breadcrumbs).ok().and_then(known_type);
+    let applied = match ty {
+        Some(ty) => {
+            let value = parse(&ty, text).map_err(|kind| item.error(kind))?;
+            path::set(root, breadcrumbs, value)
+        }
+        None if text.is_empty() => path::set_variant(root, breadcrumbs, "None", None),
+        None => match path::set_variant(root, breadcrumbs, text, None) {
+            Err(PathError {
+                kind: PathErrorKind::UnknownVariant { type_name, .. },
+                ..
+            }) => {
+                return Err(item.error(OverrideErrorKind::InvalidValue {
+                    expected: type_name,
+                    value: text.to_string(),
+                }))
+            }
+            applied => applied,
+        },
+    };
+    applied.map_err(|err| item.error(OverrideErrorKind::Path(Box::new(err))))
+}
+
+/// Apply each of `overrides` to `root` in turn, stopping at the first which
+/// cannot be applied.
+pub fn apply_typed<T: Navigate + TypeInfo>(
+    root: &mut T,
+    overrides: &[Override],
+) -> Result<(), OverrideError> {
+    for item in overrides {
+        apply_one_typed(root, item)?;
+    }
+    Ok(())
+}
+
+/// Apply `item` to `root`, resolving its key against the `TypeInfo` of `T`.
+pub fn apply_one_typed<T: Navigate + TypeInfo>(
+    root: &mut T,
+    item: &Override,
+) -> Result<(), OverrideError> {
+    let (breadcrumbs, ty) = resolve(T::type_info(), &item.key).map_err(|kind| item.error(kind))?;
+    let text = item.value.as_str();
+    let applied = match &ty.kind {
+        TypeKind::Option(_) if text.is_empty() => {
+            path::set_variant(root, &breadcrumbs, "None", None)
+        }
+        TypeKind::Option(some) => {
+            let value = parse(&some.get(), text).map_err(|kind| item.error(kind))?;
+            path::set_variant(root, &breadcrumbs, "Some", Some(value))
+        }
+        TypeKind::Enum(variants) => {
+            let variant = variants
+                .iter()
+                .find(|variant| {
+                    variant.fields.is_empty() && variant.name.eq_ignore_ascii_case(text)
+                })
+                .ok_or_else(|| {
+                    item.error(OverrideErrorKind::InvalidValue {
+                        expected: ty.name,
+                        value: text.to_string(),
+                    })
+                })?;
+            path::set_variant(root, &breadcrumbs, variant.name, None)
+        }
+        _ => {
+            let value = parse(&ty, text).map_err(|kind| item.error(kind))?;
+            path::set(root, &breadcrumbs, value)
+        }
+    };
+    applied.map_err(|err| item.error(OverrideErrorKind::Path(Box::new(err))))
+}
+
+// The node to which the segments of a key lead within `node`.
+fn find<'a>(mut node: &'a Node, mut segments: &[String]) -> Result<&'a Node, OverrideErrorKind> {
+    while let Some(segment) = segments.first() {
+        let named = node.children.iter().find_map(|child| {
+            let steps: Vec<&Breadcrumb> = child
+                .breadcrumbs
+                .iter()
+                .skip(node.breadcrumbs.len())
+                .collect();
+            let consumed = match steps.as_slice() {
+                [step] if names(step, segment) => 1,
+                // the field of a variant is named along with the variant
+                [Breadcrumb::Variant(variant), step]
+                    if variant.eq_ignore_ascii_case(segment)
+                        && segments.get(1).is_some_and(|next| names(step, next)) =>
+                {
+                    2
+                }
+                _ => return None,
+            };
+            Some((consumed, child))
+        });
+        match named {
+            Some((consumed, child)) => {
+                node = child;
+                segments = &segments[consumed..];
+            }
+            None => {
+                node = only_variant(node).ok_or_else(|| OverrideErrorKind::UnknownKey {
+                    segment: segment.to_string(),
+                })?;
+            }
+        }
+    }
+    Ok(node)
+}
+
+// the only child of `node`, if it is reached by a single variant
+fn only_variant(node: &Node) -> Option<&Node> {
+    match node.children.as_slice() {
+        [child] if child.breadcrumbs.len() == node.breadcrumbs.len() + 1 => {
+            match child.breadcrumbs.back() {
+                Some(Breadcrumb::Variant(_)) => Some(child),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+// true if `segment` names the child reached by `breadcrumb`
+fn names(breadcrumb: &Breadcrumb, segment: &str) -> bool {
+    match breadcrumb {
+        Breadcrumb::Variant(name) | Breadcrumb::Field(name) => name.eq_ignore_ascii_case(segment),
+        Breadcrumb::TupleIndex(idx) => idx.to_string() == segment,
+        // map entries are visited at the `Debug` representation of their keys
+        Breadcrumb::Index(key) => {
+            let mut chars = segment.chars();
+            *key == segment
+                || *key == format!("{:?}", segment)
+                || matches!((chars.next(), chars.next()), (Some(c), None) if *key == format!("{:?}", c))
+        }
+        Breadcrumb::SetMember(_) | Breadcrumb::MapKey(_) => false,
+    }
+}
+
+// The breadcrumbs to which the segments of a key lead within a value of type
+// `ty`, and the type of the value there.
+fn resolve(mut ty: Type, segments: &[String]) -> Result<(Breadcrumbs, Type), OverrideErrorKind> {
+    let mut breadcrumbs = Breadcrumbs::new();
+    let mut segments = segments.iter().peekable();
+    while let Some(&segment) = segments.peek() {
+        let unknown = || OverrideErrorKind::UnknownKey {
+            segment: segment.to_string(),
+        };
+        let (steps, child) = match &ty.kind {
+            TypeKind::Option(some) => {
+                // an option has no segment of its own
+                breadcrumbs.push_back(Breadcrumb::Variant("Some"));
+                ty = some.get();
+                continue;
+            }
+            TypeKind::Struct(fields) => field(fields, segment).ok_or_else(unknown)?,
+            TypeKind::Enum(variants) => {
+                let variant = variants
+                    .iter()
+                    .find(|variant| variant.name.eq_ignore_ascii_case(segment))
+                    .ok_or_else(unknown)?;
+                // a variant is not a value, so must be followed by a field
+                segments.next();
+                let segment = segments.peek().ok_or_else(unknown)?;
+                let (mut steps, child) = field(&variant.fields, segment).ok_or_else(|| {
+                    OverrideErrorKind::UnknownKey {
+                        segment: segment.to_string(),
+                    }
+                })?;
+                steps.insert(0, Breadcrumb::Variant(variant.name));
+                (steps, child)
+            }
+            TypeKind::Result(ok, err) => {
+                let (name, child) = if segment.eq_ignore_ascii_case("ok") {
+                    ("Ok", ok)
+                } else if segment.eq_ignore_ascii_case("err") {
+                    ("Err", err)
+                } else {
+                    return Err(unknown());
+                };
+                (vec![Breadcrumb::Variant(name)], child.get())
+            }
+            TypeKind::Tuple(items) => {
+                let idx: usize = segment.parse().map_err(|_| unknown())?;
+                let item = items.get(idx).ok_or_else(unknown)?;
+                (vec![Breadcrumb::TupleIndex(idx)], item.get())
+            }
+            TypeKind::Array(item, _) | TypeKind::List(item) => {
+                let idx: usize = segment.parse().map_err(|_| unknown())?;
+                (vec![Breadcrumb::Index(idx.to_string())], item.get())
+            }
+            TypeKind::Map(key, value) => (
+                vec![Breadcrumb::Index(map_key(&key.get(), segment))],
+                value.get(),
+            ),
+            // only objects can be descended into, as the shape of a JSON value
+            // is not known in advance
+            TypeKind::Json => (
+                vec![
+                    Breadcrumb::Variant("Object"),
+                    Breadcrumb::Index(segment.to_string()),
+                ],
+                ty.clone(),
+            ),
+            TypeKind::Set(_) | TypeKind::Primitive(_) | TypeKind::Opaque => return Err(unknown()),
+        };
+        segments.next();
+        breadcrumbs.extend(steps);
+        ty = child;
+    }
+    Ok((breadcrumbs, ty))
+}
+
+// the breadcrumb leading to the field named by `segment`, and its type
+fn field(fields: &Fields, segment: &str) -> Option<(Vec<Breadcrumb>, Type)> {
+    match fields {
+        Fields::Unit => None,
+        Fields::Named(fields) => fields
+            .iter()
+            .find(|field| field.name.eq_ignore_ascii_case(segment))
+            .map(|field| (vec![Breadcrumb::Field(field.name)], field.ty.get())),
+        Fields::Unnamed(fields) => {
+            let idx: usize = segment.parse().ok()?;
+            fields
+                .get(idx)
+                .map(|ty| (vec![Breadcrumb::TupleIndex(idx)], ty.get()))
+        }
+    }
+}
+
+// Map entries are visited at the `Debug` representation of their keys, so
+// string and character keys are quoted.
+fn map_key(key: &Type, segment: &str) -> String {
+    match key.kind {
+        TypeKind::Primitive(Primitive::String) => format!("{:?}", segment),
+        TypeKind::Primitive(Primitive::Char) if segment.chars().count() == 1 => {
+            format!(
+                "{:?}",
+                segment.chars().next().expect("segment has one char")
+            )
+        }
+        _ => segment.to_string(),
+    }
+}
+
+// the leaf value of type `ty` given by `text`
+fn parse(ty: &Type, text: &str) -> Result<Box<dyn Any>, OverrideErrorKind> {
+    fn from_str<T: FromStr + Any>(text: &str) -> Option<Box<dyn Any>> {
+        text.parse::<T>()
+            .ok()
+            .map(|value| Box::new(value) as Box<dyn Any>)
+    }
+
+    let parsed = match &ty.kind {
+        TypeKind::Primitive(primitive) => match primitive {
+            Primitive::Bool => from_str::<bool>(&text.to_ascii_lowercase()),
+            Primitive::Char => from_str::<char>(text),
+            Primitive::U8 => from_str::<u8>(text),
+            Primitive::U16 => from_str::<u16>(text),
+            Primitive::U32 => from_str::<u32>(text),
+            Primitive::U64 => from_str::<u64>(text),
+            Primitive::U128 => from_str::<u128>(text),
+            Primitive::Usize => from_str::<usize>(text),
+            Primitive::I8 => from_str::<i8>(text),
+            Primitive::I16 => from_str::<i16>(text),
+            Primitive::I32 => from_str::<i32>(text),
+            Primitive::I64 => from_str::<i64>(text),
+            Primitive::I128 => from_str::<i128>(text),
+            Primitive::Isize => from_str::<isize>(text),
+            Primitive::F32 => from_str::<f32>(text),
+            Primitive::F64 => from_str::<f64>(text),
+            // a `&'static str` cannot be made from text
+            Primitive::String if ty.name == std::any::type_name::<String>() => {
+                Some(Box::new(text.to_string()) as Box<dyn Any>)
+            }
+            Primitive::String => return Err(OverrideErrorKind::Unsupported { type_name: ty.name }),
+            #[cfg(feature = "serde-json")]
+            Primitive::Number => from_str::<serde_json::Number>(text),
+            #[cfg(not(feature = "serde-json"))]
+            Primitive::Number => None,
+        },
+        #[cfg(feature = "serde-json")]
+        TypeKind::Json => Some(Box::new(
+            serde_json::from_str(text)
+                .unwrap_or_else(|_| serde_json::Value::String(text.to_string())),
+        ) as Box<dyn Any>),
+        _ => return Err(OverrideErrorKind::Unsupported { type_name: ty.name }),
+    };
+    parsed.ok_or_else(|| OverrideErrorKind::InvalidValue {
+        expected: ty.name,
+        value: text.to_string(),
+    })
+}
@@ -0,0 +1,995 @@
+//! Look up and modify values by the breadcrumbs at which they are visited.
+//!
+//! The [`Navigate`] trait finds the child of a value reached by the first
+//! breadcrumbs of a path. It is implemented for the same types as
+//! `Introspect`, and by `#[derive(Navigate)]`, so the breadcrumbs passed to
+//! an introspection visitor can later be used to [`get`] that value again,
+//! or to [`set`] a new value in its place.
+//!
+//! Collections can also grow and shrink: [`insert`] adds an element to the
+//! collection at a path, and [`remove`] removes the element at a path. A list
+//! is appended to, while a map is given a `(key, value)` tuple, and replaces
+//! any entry with an equal key.
+//!
+//! The active variant of an enum can be changed with [`set_variant`], given
+//! the variant's field if it has one.
+//!
+//! A path leads through an enum variant only while that variant is active;
+//! otherwise, a [`PathErrorKind::InactiveVariant`] is returned. Values which
+//! cannot be modified without corrupting their container, such as set
+//! members and map keys, can be read but not written. Values which are
+//! computed during introspection rather than stored, such as the fields of a
+//! `serde_json::Error` or the contents of a `serde_json::Number`, cannot be
+//! reached at all.
+
+use crate::{Breadcrumb, Breadcrumbs};
+#[cfg(feature = "derive")]
+pub use spectacle_derive::Navigate;
+use std::{any::Any, fmt};
+
+/// Conversions of a value into `Any`, implemented for every `'static` type.
+///
+/// This is a supertrait of [`Navigate`], so that the conversions are
+/// available for `dyn Navigate`.
+pub trait AnyValue: Any {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    /// The name of the value's type.
+    fn type_name(&self) -> &'static str;
+    /// Replace the value with `value` if it has the same type, or return it.
+    fn replace(&mut self, value: Box<dyn Any>) -> Result<(), Box<dyn Any>>;
+}
+
+impl<T: Any> AnyValue for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<T>()
+    }
+
+    fn replace(&mut self, value: Box<dyn Any>) -> Result<(), Box<dyn Any>> {
+        *self = *value.downcast::<T>()?;
+        Ok(())
+    }
+}
+
+/// Find the children of a value by their breadcrumbs.
+///
+/// Each method is passed a non-empty path, and returns the child reached by
+/// its first breadcrumbs along with the number of breadcrumbs consumed. That
+/// is usually one, but is two for the field of an enum variant, which follows
+/// a `Breadcrumb::Variant`. The default implementations are appropriate for
+/// leaf types, which have no children.
+pub trait Navigate: AnyValue {
+    /// The child reached by the first breadcrumbs of `path`.
+    fn child(&self, path: &[Breadcrumb]) -> Result<(usize, &dyn Navigate), PathErrorKind> {
+        Err(PathErrorKind::not_found(path))
+    }
+
+    /// The child reached by the first breadcrumbs of `path`, mutably.
+    fn child_mut(
+        &mut self,
+        path: &[Breadcrumb],
+    ) -> Result<(usize, &mut dyn Navigate), PathErrorKind> {
+        Err(PathErrorKind::not_found(path))
+    }
+
+    /// Add `value` to this collection.
+    fn insert(&mut self, value: Box<dyn Any>) -> Result<(), PathErrorKind> {
+        let _ = value;
+        Err(PathErrorKind::NotACollection {
+            type_name: AnyValue::type_name(&*self),
+        })
+    }
+
+    /// Remove the child reached by `breadcrumb` from this collection, and
+    /// return it.
+    fn remove(&mut self, breadcrumb: &Breadcrumb) -> Result<Box<dyn Any>, PathErrorKind> {
+        let _ = breadcrumb;
+        Err(PathErrorKind::NotACollection {
+            type_name: AnyValue::type_name(&*self),
+        })
+    }
+
+    /// Replace this enum with its variant named `variant`, which has no fields
+    /// if `value` is `None`, or has the single field `value`.
+    fn set_variant(
+        &mut self,
+        variant: &str,
+        value: Option<Box<dyn Any>>,
+    ) -> Result<(), PathErrorKind> {
+        Err(PathErrorKind::UnknownVariant {
+            type_name: AnyValue::type_name(&*self),
+            variant: variant.to_string(),
+            fields: value.is_some() as usize,
+        })
+    }
+}
+
+/// Why a path could not be followed.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum PathErrorKind {
+    /// The value has no child reached by this breadcrumb.
+    NotFound(Breadcrumb),
+    /// The path leads through a variant of an enum other than its active one.
+    InactiveVariant {
+        requested: &'static str,
+        active: &'static str,
+    },
+    /// The child reached by this breadcrumb cannot be modified in place.
+    Immutable(Breadcrumb),
+    /// The value to set is not of the same type as the value it would replace,
+    /// or the value to insert is not of the collection's element type.
+    TypeMismatch { expected: &'static str },
+    /// Elements cannot be inserted into or removed from a value of this type.
+    NotACollection { type_name: &'static str },
+    /// The path is empty, so leads to no child, nor to an element of a
+    /// collection.
+    EmptyPath,
+    /// The type has no variant of this name with this number of fields, which
+    /// is zero or one.
+    UnknownVariant {
+        type_name: &'static str,
+        variant: String,
+        fields: usize,
+    },
+    /// The variant has several fields, so cannot be set from a single value.
+    UnsupportedVariant {
+        type_name: &'static str,
+        variant: &'static str,
+        fields: usize,
+    },
+}
+
+impl PathErrorKind {
+    /// The error for a path whose first breadcrumb leads nowhere.
+    pub fn not_found(path: &[Breadcrumb]) -> PathErrorKind {
+        match path.first() {
+            Some(breadcrumb) => PathErrorKind::NotFound(breadcrumb.clone()),
+            None => PathErrorKind::EmptyPath,
+        }
+    }
+
+    /// The error for a path into an enum whose active variant is `active`,
+    /// which could not be followed.
+    pub fn for_variant(path: &[Breadcrumb], active: &'static str) -> PathErrorKind {
+        match path {
+            [Breadcrumb::Variant(requested), ..] if *requested != active => {
+                PathErrorKind::InactiveVariant { requested, active }
+            }
+            [Breadcrumb::Variant(_), next, ..] => PathErrorKind::NotFound(next.clone()),
+            _ => PathErrorKind::not_found(path),
+        }
+    }
+}
+
+/// A path which could not be followed, and where it failed.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PathError {
+    /// The breadcrumbs leading to the last value reached.
+    pub breadcrumbs: Breadcrumbs,
+    pub kind: PathErrorKind,
+}
+
+/// A path error displays as the path to the last value reached, and why the
+/// path could not be followed from there: `$.mode: variant Fixed is not active`.
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: ", crate::format_path(&self.breadcrumbs))?;
+        match &self.kind {
+            PathErrorKind::NotFound(breadcrumb) => write!(f, "no child at {}", breadcrumb),
+            PathErrorKind::InactiveVariant { requested, active } => write!(
+                f,
+                "variant {} is not active; the active variant is {}",
+                requested, active
+            ),
+            PathErrorKind::Immutable(breadcrumb) => {
+                write!(f, "the child at {} cannot be modified", breadcrumb)
+            }
+            PathErrorKind::TypeMismatch { expected } => {
+                write!(f, "expected a value of type {}", expected)
+            }
+            PathErrorKind::NotACollection { type_name } => {
+                write!(f, "a value of type {} is not a collection", type_name)
+            }
+            PathErrorKind::EmptyPath => write!(f, "an empty path leads to no child"),
+            PathErrorKind::UnknownVariant {
+                type_name,
+                variant,
+                fields,
+            } => write!(
+                f,
+                "{} has no variant {} with {}",
+                type_name,
+                variant,
+                if *fields == 0 {
+                    "no fields"
+                } else {
+                    "one field"
+                }
+            ),
+            PathErrorKind::UnsupportedVariant {
+                type_name,
+                variant,
+                fields,
+            } => write!(
+                f,
+                "variant {} of {} has {} fields, so cannot be set from one value",
+                variant, type_name, fields
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+fn error(path: &[Breadcrumb], position: usize, kind: PathErrorKind) -> PathError {
+    PathError {
+        breadcrumbs: path[..position].iter().cloned().collect(),
+        kind,
+    }
+}
+
+/// The value at `breadcrumbs` within `root`.
+pub fn get<'a>(
+    root: &'a dyn Navigate,
+    breadcrumbs: &Breadcrumbs,
+) -> Result<&'a dyn Any, PathError> {
+    let path: Vec<Breadcrumb> = breadcrumbs.iter().cloned().collect();
+    let mut node = root;
+    let mut position = 0;
+    while position < path.len() {
+        let (consumed, child) = node
+            .child(&path[position..])
+            .map_err(|kind| error(&path, position, kind))?;
+        node = child;
+        position += consumed;
+    }
+    Ok(node.as_any())
+}
+
+/// The value at `breadcrumbs` within `root`, mutably.
+pub fn get_mut<'a>(
+    root: &'a mut dyn Navigate,
+    breadcrumbs: &Breadcrumbs,
+) -> Result<&'a mut dyn Any, PathError> {
+    Ok(navigate_mut(root, breadcrumbs)?.as_any_mut())
+}
+
+/// Replace the value at `breadcrumbs` within `root` with `value`, which must
+/// be of the same type.
+pub fn set(
+    root: &mut dyn Navigate,
+    breadcrumbs: &Breadcrumbs,
+    value: Box<dyn Any>,
+) -> Result<(), PathError> {
+    let node = navigate_mut(root, breadcrumbs)?;
+    let expected = (*node).type_name();
+    node.replace(value).map_err(|_| PathError {
+        breadcrumbs: breadcrumbs.clone(),
+        kind: PathErrorKind::TypeMismatch { expected },
+    })
+}
+
+/// Add `value` to the collection at `breadcrumbs` within `root`.
+///
+/// Lists are appended to and sets are added to. Maps are given a `(key,
+/// value)` tuple. A `serde_json::Value` may be given in place of the array or
+/// object it holds.
+pub fn insert(
+    root: &mut dyn Navigate,
+    breadcrumbs: &Breadcrumbs,
+    value: Box<dyn Any>,
+) -> Result<(), PathError> {
+    navigate_mut(root, breadcrumbs)?
+        .insert(value)
+        .map_err(|kind| PathError {
+            breadcrumbs: breadcrumbs.clone(),
+            kind,
+        })
+}
+
+/// Remove the element at `breadcrumbs` within `root` from its collection, and
+/// return it.
+///
+/// Removing a map entry by its `Breadcrumb::Index` returns its value, and by
+/// its `Breadcrumb::MapKey` returns its key. As for [`insert`], the element
+/// may be given within a `serde_json::Value` rather than the array or object
+/// it holds.
+pub fn remove(
+    root: &mut dyn Navigate,
+    breadcrumbs: &Breadcrumbs,
+) -> Result<Box<dyn Any>, PathError> {
+    let mut parent = breadcrumbs.clone();
+    let last = parent.pop_back().ok_or(PathError {
+        breadcrumbs: Breadcrumbs::new(),
+        kind: PathErrorKind::EmptyPath,
+    })?;
+    navigate_mut(root, &parent)?
+        .remove(&last)
+        .map_err(|kind| PathError {
+            breadcrumbs: parent,
+            kind,
+        })
+}
+
+/// Replace the enum at `breadcrumbs` within `root` with its variant named
+/// `variant`, which has no fields if `value` is `None`, or has the single
+/// field `value`.
+pub fn set_variant(
+    root: &mut dyn Navigate,
+    breadcrumbs: &Breadcrumbs,
+    variant: &str,
+    value: Option<Box<dyn Any>>,
+) -> Result<(), PathError> {
+    navigate_mut(root, breadcrumbs)?
+        .set_variant(variant, value)
+        .map_err(|kind| PathError {
+            breadcrumbs: breadcrumbs.clone(),
+            kind,
+        })
+}
+
+fn navigate_mut<'a>(
+    root: &'a mut dyn Navigate,
+    breadcrumbs: &Breadcrumbs,
+) -> Result<&'a mut dyn Navigate, PathError> {
+    let path: Vec<Breadcrumb> = breadcrumbs.iter().cloned().collect();
+    let mut node = root;
+    let mut position = 0;
+    while position < path.len() {
+        let (consumed, child) = node
+            .child_mut(&path[position..])
+            .map_err(|kind| error(&path, position, kind))?;
+        node = child;
+        position += consumed;
+    }
+    Ok(node)
+}
+
+// the index named by `path`'s first breadcrumb, if it is an `Index`
+fn index(path: &[Breadcrumb]) -> Option<usize> {
+    match path.first() {
+        Some(Breadcrumb::Index(index)) => index.parse().ok(),
+        _ => None,
+    }
+}
+
+// `value` as a `T`, or the error for an element of the wrong type
+fn downcast<T: Any>(value: Box<dyn Any>) -> Result<T, PathErrorKind> {
+    value
+        .downcast::<T>()
+        .map(|value| *value)
+        .map_err(|_| PathErrorKind::TypeMismatch {
+            expected: std::any::type_name::<T>(),
+        })
+}
+
+macro_rules! impl_leaf {
+    ($($t:ty),* $(,)?) => {
+        $(impl Navigate for $t {})*
+    };
+}
+
+impl_leaf!(
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+    String,
+    &'static str,
+);
+
+// The items of a `'static` slice are visited, but cannot be modified.
+impl<T: Navigate> Navigate for &'static [T] {
+    fn child(&self, path: &[Breadcrumb]) -> Result<(usize, &dyn Navigate), PathErrorKind> {
+        match index(path).and_then(|idx| self.get(idx)) {
+            Some(item) => Ok((1, item)),
+            None => Err(PathErrorKind::not_found(path)),
+        }
+    }
+
+    fn child_mut(
+        &mut self,
+        path: &[Breadcrumb],
+    ) -> Result<(usize, &mut dyn Navigate), PathErrorKind> {
+        self.child(path)?;
+        Err(PathErrorKind::Immutable(path[0].clone()))
+    }
+}
+
+impl<T: Navigate, const N: usize> Navigate for [T; N] {
+    fn child(&self, path: &[Breadcrumb]) -> Result<(usize, &dyn Navigate), PathErrorKind> {
+        match index(path).and_then(|idx| self.get(idx)) {
+            Some(item) => Ok((1, item)),
+            None => Err(PathErrorKind::not_found(path)),
+        }
+    }
+
+    fn child_mut(
+        &mut self,
+        path: &[Breadcrumb],
+    ) -> Result<(usize, &mut dyn Navigate), PathErrorKind> {
+        match index(path).and_then(move |idx| self.get_mut(idx)) {
+            Some(item) => Ok((1, item)),
+            None => Err(PathErrorKind::not_found(path)),
+        }
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($t:ident),*) => {
+        impl<$($t: Navigate),*> Navigate for ($($t,)*) {
+            #[allow(non_snake_case, unused_variables)]
+            fn child(&self, path: &[Breadcrumb]) -> Result<(usize, &dyn Navigate), PathErrorKind> {
+                let ($($t,)*) = self;
+                let items: Vec<&dyn Navigate> = vec![$($t),*];
+                match path.first() {
+                    Some(Breadcrumb::TupleIndex(idx)) if *idx < items.len() => {
+                        Ok((1, items.into_iter().nth(*idx).expect("index is in bounds")))
+                    }
+                    _ => Err(PathErrorKind::not_found(path)),
+                }
+            }
+
+            #[allow(non_snake_case, unused_variables)]
+            fn child_mut(
+                &mut self,
+                path: &[Breadcrumb],
+            ) -> Result<(usize, &mut dyn Navigate), PathErrorKind> {
+                let ($($t,)*) = self;
+                let items: Vec<&mut dyn Navigate> = vec![$($t),*];
+                match path.first() {
+                    Some(Breadcrumb::TupleIndex(idx)) if *idx < items.len() => {
+                        Ok((1, items.into_iter().nth(*idx).expect("index is in bounds")))
+                    }
+                    _ => Err(PathErrorKind::not_found(path)),
+                }
+            }
+        }
+    };
+}
+
+// implement for a tuple of every suffix of the list of type parameters
+macro_rules! impl_tuples {
+    () => {
+        impl_tuple!();
+    };
+    ($t:ident $(, $ts:ident)*) => {
+        impl_tuple!($t $(, $ts)*);
+        impl_tuples!($($ts),*);
+    };
+}
+
+impl_tuples!(
+    T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16, T17, T18, T19, T20,
+    T21, T22, T23, T24, T25, T26, T27, T28, T29, T30, T31
+);
+
+impl<T: Navigate> Navigate for Option<T> {
+    fn child(&self, path: &[Breadcrumb]) -> Result<(usize, &dyn Navigate), PathErrorKind> {
+        match (self, path.first()) {
+            (Some(t), Some(Breadcrumb::Variant("Some"))) => Ok((1, t)),
+            (Some(_), _) => Err(PathErrorKind::for_variant(path, "Some")),
+            (None, _) => Err(PathErrorKind::for_variant(path, "None")),
+        }
+    }
+
+    fn child_mut(
+        &mut self,
+        path: &[Breadcrumb],
+    ) -> Result<(usize, &mut dyn Navigate), PathErrorKind> {
+        match (self, path.first()) {
+            (Some(t), Some(Breadcrumb::Variant("Some"))) => Ok((1, t)),
+            (Some(_), _) => Err(PathErrorKind::for_variant(path, "Some")),
+            (None, _) => Err(PathErrorKind::for_variant(path, "None")),
+        }
+    }
+
+    fn set_variant(
+        &mut self,
+        variant: &str,
+        value: Option<Box<dyn Any>>,
+    ) -> Result<(), PathErrorKind> {
+        *self = match (variant, value) {
+            ("None", None) => None,
+            ("Some", Some(value)) => Some(downcast::<T>(value)?),
+            (variant, value) => {
+                return Err(PathErrorKind::UnknownVariant {
+                    type_name: std::any::type_name::<Self>(),
+                    variant: variant.to_string(),
+                    fields: value.is_some() as usize,
+                })
+            }
+        };
+        Ok(())
+    }
+}
+
+impl<T: Navigate, E: Navigate> Navigate for Result<T, E> {
+    fn child(&self, path: &[Breadcrumb]) -> Result<(usize, &dyn Navigate), PathErrorKind> {
+        match (self, path.first()) {
+            (Ok(t), Some(Breadcrumb::Variant("Ok"))) => Ok((1, t)),
+            (Err(e), Some(Breadcrumb::Variant("Err"))) => Ok((1, e)),
+            (Ok(_), _) => Err(PathErrorKind::for_variant(path, "Ok")),
+            (Err(_), _) => Err(PathErrorKind::for_variant(path, "Err")),
+        }
+    }
+
+    fn child_mut(
+        &mut self,
+        path: &[Breadcrumb],
+    ) -> Result<(usize, &mut dyn Navigate), PathErrorKind> {
+        match (self, path.first()) {
+            (Ok(t), Some(Breadcrumb::Variant("Ok"))) => Ok((1, t)),
+            (Err(e), Some(Breadcrumb::Variant("Err"))) => Ok((1, e)),
+            (Ok(_), _) => Err(PathErrorKind::for_variant(path, "Ok")),
+            (Err(_), _) => Err(PathErrorKind::for_variant(path, "Err")),
+        }
+    }
+
+    fn set_variant(
+        &mut self,
+        variant: &str,
+        value: Option<Box<dyn Any>>,
+    ) -> Result<(), PathErrorKind> {
+        *self = match (variant, value) {
+            ("Ok", Some(value)) => Ok(downcast::<T>(value)?),
+            ("Err", Some(value)) => Err(downcast::<E>(value)?),
+            (variant, value) => {
+                return Err(PathErrorKind::UnknownVariant {
+                    type_name: std::any::type_name::<Self>(),
+                    variant: variant.to_string(),
+                    fields: value.is_some() as usize,
+                })
+            }
+        };
+        Ok(())
+    }
+}
+
+// Lists remove their items by index, with `$remove`.
+macro_rules! impl_list {
+    ($($t:ident)::+, $remove:ident) => {
+        #[cfg(feature = "collections")]
+        impl<T: Navigate> Navigate for $($t)::+<T> {
+            fn child(&self, path: &[Breadcrumb]) -> Result<(usize, &dyn Navigate), PathErrorKind> {
+                match index(path).and_then(|idx| self.iter().nth(idx)) {
+                    Some(item) => Ok((1, item)),
+                    None => Err(PathErrorKind::not_found(path)),
+                }
+            }
+
+            fn child_mut(
+                &mut self,
+                path: &[Breadcrumb],
+            ) -> Result<(usize, &mut dyn Navigate), PathErrorKind> {
+                match index(path).and_then(move |idx| self.iter_mut().nth(idx)) {
+                    Some(item) => Ok((1, item)),
+                    None => Err(PathErrorKind::not_found(path)),
+                }
+            }
+
+            fn insert(&mut self, value: Box<dyn Any>) -> Result<(), PathErrorKind> {
+                self.extend(std::iter::once(downcast::<T>(value)?));
+                Ok(())
+            }
+
+            fn remove(&mut self, breadcrumb: &Breadcrumb) -> Result<Box<dyn Any>, PathErrorKind> {
+                index(std::slice::from_ref(breadcrumb))
+                    .and_then(|idx| $remove(self, idx))
+                    .map(|item| Box::new(item) as Box<dyn Any>)
+                    .ok_or_else(|| PathErrorKind::NotFound(breadcrumb.clone()))
+            }
+        }
+    };
+}
+
+#[cfg(feature = "collections")]
+fn remove_from_vec<T>(items: &mut Vec<T>, idx: usize) -> Option<T> {
+    if idx < items.len() {
+        Some(items.remove(idx))
+    } else {
+        None
+    }
+}
+
+#[cfg(feature = "collections")]
+fn remove_from_deque<T>(items: &mut std::collections::VecDeque<T>, idx: usize) -> Option<T> {
+    items.remove(idx)
+}
+
+#[cfg(feature = "collections")]
+fn remove_from_linked_list<T>(
+    items: &mut std::collections::LinkedList<T>,
+    idx: usize,
+) -> Option<T> {
+    if idx >= items.len() {
+        return None;
+    }
+    let mut tail = items.split_off(idx);
+    let item = tail.pop_front();
+    items.append(&mut tail);
+    item
+}
+
+impl_list!(Vec, remove_from_vec);
+impl_list!(std::collections::VecDeque, remove_from_deque);
+impl_list!(std::collections::LinkedList, remove_from_linked_list);
+
+// Set members may not be modified, as that could violate the invariants of
+// the set. Members are removed by their ordinal, with `$remove`.
+macro_rules! impl_set {
+    ($($t:ident)::+, $remove:ident $(, $bound:path)*) => {
+        #[cfg(feature = "collections")]
+        impl<T: Navigate $(+ $bound)*> Navigate for $($t)::+<T> {
+            fn child(&self, path: &[Breadcrumb]) -> Result<(usize, &dyn Navigate), PathErrorKind> {
+                let member = match path.first() {
+                    Some(Breadcrumb::SetMember(member)) => member,
+                    _ => return Err(PathErrorKind::not_found(path)),
+                };
+                match find_member(self.iter(), member) {
+                    Some(item) => Ok((1, item)),
+                    None => Err(PathErrorKind::not_found(path)),
+                }
+            }
+
+            fn child_mut(
+                &mut self,
+                path: &[Breadcrumb],
+            ) -> Result<(usize, &mut dyn Navigate), PathErrorKind> {
+                self.child(path)?;
+                Err(PathErrorKind::Immutable(path[0].clone()))
+            }
+
+            fn insert(&mut self, value: Box<dyn Any>) -> Result<(), PathErrorKind> {
+                self.extend(std::iter::once(downcast::<T>(value)?));
+                Ok(())
+            }
+
+            fn remove(&mut self, breadcrumb: &Breadcrumb) -> Result<Box<dyn Any>, PathErrorKind> {
+                let member = match breadcrumb {
+                    Breadcrumb::SetMember(member) => member.parse().ok(),
+                    _ => None,
+                };
+                member
+                    .and_then(|ordinal| $remove(self, ordinal))
+                    .map(|item| Box::new(item) as Box<dyn Any>)
+                    .ok_or_else(|| PathErrorKind::NotFound(breadcrumb.clone()))
+            }
+        }
+    };
+}
+
+// the member of an ordered set or heap with the given ordinal
+#[cfg(feature = "collections")]
+fn find_member<'a, T: 'a>(mut iter: impl Iterator<Item = &'a T>, member: &str) -> Option<&'a T> {
+    iter.nth(member.parse().ok()?)
+}
+
+// the member of an ordered set is taken by a clone of itself
+#[cfg(feature = "collections")]
+fn remove_from_btree_set<T: Ord + Clone>(
+    set: &mut std::collections::BTreeSet<T>,
+    ordinal: usize,
+) -> Option<T> {
+    let member = set.iter().nth(ordinal)?.clone();
+    set.take(&member)
+}
+
+// the members of a heap are visited in the order of its underlying vector, from
+// which the member is removed before the heap is rebuilt around the others
+#[cfg(feature = "collections")]
+fn remove_from_heap<T: Ord>(
+    heap: &mut std::collections::BinaryHeap<T>,
+    ordinal: usize,
+) -> Option<T> {
+    if ordinal >= heap.len() {
+        return None;
+    }
+    let mut items = std::mem::take(heap).into_vec();
+    let item = items.swap_remove(ordinal);
+    *heap = items.into();
+    Some(item)
+}
+
+impl_set!(
+    std::collections::BTreeSet,
+    remove_from_btree_set,
+    Ord,
+    Clone
+);
+impl_set!(std::collections::BinaryHeap, remove_from_heap, Ord);
+
+#[cfg(feature = "collections")]
+impl<T: Navigate + Eq + std::hash::Hash + Clone> Navigate for std::collections::HashSet<T> {
+    fn child(&self, path: &[Breadcrumb]) -> Result<(usize, &dyn Navigate), PathErrorKind> {
+        let member = match path.first() {
+            Some(Breadcrumb::SetMember(member)) => member,
+            _ => return Err(PathErrorKind::not_found(path)),
+        };
+        match crate::hash_set_members(self, false)
+            .into_iter()
+            .find(|(label, _)| label == member)
+        {
+            Some((_, item)) => Ok((1, item)),
+            None => Err(PathErrorKind::not_found(path)),
+        }
+    }
+
+    fn child_mut(
+        &mut self,
+        path: &[Breadcrumb],
+    ) -> Result<(usize, &mut dyn Navigate), PathErrorKind> {
+        self.child(path)?;
+        Err(PathErrorKind::Immutable(path[0].clone()))
+    }
+
+    fn insert(&mut self, value: Box<dyn Any>) -> Result<(), PathErrorKind> {
+        self.extend(std::iter::once(downcast::<T>(value)?));
+        Ok(())
+    }
+
+    fn remove(&mut self, breadcrumb: &Breadcrumb) -> Result<Box<dyn Any>, PathErrorKind> {
+        let member = match breadcrumb {
+            Breadcrumb::SetMember(member) => member,
+            _ => return Err(PathErrorKind::NotFound(breadcrumb.clone())),
+        };
+        let found = crate::hash_set_members(self, false)
+            .into_iter()
+            .find(|(label, _)| label == member)
+            .map(|(_, item)| item.clone());
+        found
+            .and_then(|item| self.take(&item))
+            .map(|item| Box::new(item) as Box<dyn Any>)
+            .ok_or_else(|| PathErrorKind::NotFound(breadcrumb.clone()))
+    }
+}
+
+// Map keys may not be modified, as that could violate the invariants of the
+// map. Entries are found by the `Debug` representation of their keys, and
+// removed by a clone of the key found.
+macro_rules! impl_map {
+    ($($t:ident)::+ $(, $bound:path)*) => {
+        #[cfg(feature = "collections")]
+        impl<K, V> Navigate for $($t)::+<K, V>
+        where
+            K: Navigate + std::fmt::Debug + Clone $(+ $bound)*,
+            V: Navigate,
+        {
+            fn child(&self, path: &[Breadcrumb]) -> Result<(usize, &dyn Navigate), PathErrorKind> {
+                let found = match path.first() {
+                    Some(Breadcrumb::Index(key)) => self
+                        .iter()
+                        .find(|(k, _)| format!("{:?}", k) == *key)
+                        .map(|(_, v)| v as &dyn Navigate),
+                    Some(Breadcrumb::MapKey(key)) => self
+                        .keys()
+                        .find(|k| format!("{:?}", k) == *key)
+                        .map(|k| k as &dyn Navigate),
+                    _ => None,
+                };
+                found
+                    .map(|child| (1, child))
+                    .ok_or_else(|| PathErrorKind::not_found(path))
+            }
+
+            fn child_mut(
+                &mut self,
+                path: &[Breadcrumb],
+            ) -> Result<(usize, &mut dyn Navigate), PathErrorKind> {
+                match path.first() {
+                    Some(Breadcrumb::Index(key)) => self
+                        .iter_mut()
+                        .find(|(k, _)| format!("{:?}", k) == *key)
+                        .map(|(_, v)| (1, v as &mut dyn Navigate))
+                        .ok_or_else(|| PathErrorKind::not_found(path)),
+                    _ => {
+                        self.child(path)?;
+                        Err(PathErrorKind::Immutable(path[0].clone()))
+                    }
+                }
+            }
+
+            fn insert(&mut self, value: Box<dyn Any>) -> Result<(), PathErrorKind> {
+                self.extend(std::iter::once(downcast::<(K, V)>(value)?));
+                Ok(())
+            }
+
+            fn remove(&mut self, breadcrumb: &Breadcrumb) -> Result<Box<dyn Any>, PathErrorKind> {
+                let find = |key: &str| self.keys().find(|k| format!("{:?}", k) == key).cloned();
+                let removed = match breadcrumb {
+                    Breadcrumb::Index(key) => find(key)
+                        .and_then(|k| self.remove_entry(&k))
+                        .map(|(_, v)| Box::new(v) as Box<dyn Any>),
+                    Breadcrumb::MapKey(key) => find(key)
+                        .and_then(|k| self.remove_entry(&k))
+                        .map(|(k, _)| Box::new(k) as Box<dyn Any>),
+                    _ => None,
+                };
+                removed.ok_or_else(|| PathErrorKind::NotFound(breadcrumb.clone()))
+            }
+        }
+    };
+}
+
+impl_map!(std::collections::HashMap, Eq, std::hash::Hash);
+impl_map!(std::collections::BTreeMap, Ord);
+
+// The contents of a `Serialized` value are visited as they are serialized,
+// rather than as they are stored, so it can only be replaced as a whole.
+#[cfg(feature = "serde")]
+impl<T: 'static> Navigate for crate::serialize::Serialized<T> {}
+
+#[cfg(feature = "serde-json")]
+impl_leaf!(
+    serde_json::Number,
+    serde_json::Error,
+    serde_json::error::Category,
+    Box<serde_json::value::RawValue>,
+);
+
+#[cfg(feature = "serde-json")]
+impl Navigate for serde_json::Map<String, serde_json::Value> {
+    fn child(&self, path: &[Breadcrumb]) -> Result<(usize, &dyn Navigate), PathErrorKind> {
+        let found = match path.first() {
+            Some(Breadcrumb::Index(key)) => self.get(key).map(|v| v as &dyn Navigate),
+            Some(Breadcrumb::MapKey(key)) => {
+                self.keys().find(|k| *k == key).map(|k| k as &dyn Navigate)
+            }
+            _ => None,
+        };
+        found
+            .map(|child| (1, child))
+            .ok_or_else(|| PathErrorKind::not_found(path))
+    }
+
+    fn child_mut(
+        &mut self,
+        path: &[Breadcrumb],
+    ) -> Result<(usize, &mut dyn Navigate), PathErrorKind> {
+        match path.first() {
+            Some(Breadcrumb::Index(key)) => self
+                .get_mut(key)
+                .map(|v| (1, v as &mut dyn Navigate))
+                .ok_or_else(|| PathErrorKind::not_found(path)),
+            _ => {
+                self.child(path)?;
+                Err(PathErrorKind::Immutable(path[0].clone()))
+            }
+        }
+    }
+
+    fn insert(&mut self, value: Box<dyn Any>) -> Result<(), PathErrorKind> {
+        self.extend(std::iter::once(downcast::<(String, serde_json::Value)>(
+            value,
+        )?));
+        Ok(())
+    }
+
+    fn remove(&mut self, breadcrumb: &Breadcrumb) -> Result<Box<dyn Any>, PathErrorKind> {
+        let removed = match breadcrumb {
+            Breadcrumb::Index(key) => self.remove(key).map(|v| Box::new(v) as Box<dyn Any>),
+            Breadcrumb::MapKey(key) => self
+                .remove(key)
+                .map(|_| Box::new(key.clone()) as Box<dyn Any>),
+            _ => None,
+        };
+        removed.ok_or_else(|| PathErrorKind::NotFound(breadcrumb.clone()))
+    }
+}
+
+#[cfg(feature = "serde-json")]
+fn variant_name(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "Null",
+        serde_json::Value::Bool(_) => "Bool",
+        serde_json::Value::Number(_) => "Number",
+        serde_json::Value::String(_) => "String",
+        serde_json::Value::Array(_) => "Array",
+        serde_json::Value::Object(_) => "Object",
+    }
+}
+
+#[cfg(feature = "serde-json")]
+impl Navigate for serde_json::Value {
+    fn child(&self, path: &[Breadcrumb]) -> Result<(usize, &dyn Navigate), PathErrorKind> {
+        let active = variant_name(self);
+        if path.first() != Some(&Breadcrumb::Variant(active)) {
+            return Err(PathErrorKind::for_variant(path, active));
+        }
+        Ok((
+            1,
+            match self {
+                serde_json::Value::Null => &(),
+                serde_json::Value::Bool(x) => x,
+                serde_json::Value::Number(x) => x,
+                serde_json::Value::String(x) => x,
+                serde_json::Value::Array(x) => x,
+                serde_json::Value::Object(x) => x,
+            },
+        ))
+    }
+
+    fn child_mut(
+        &mut self,
+        path: &[Breadcrumb],
+    ) -> Result<(usize, &mut dyn Navigate), PathErrorKind> {
+        let active = variant_name(self);
+        if path.first() != Some(&Breadcrumb::Variant(active)) {
+            return Err(PathErrorKind::for_variant(path, active));
+        }
+        match self {
+            // there is no `()` within a `Value::Null` to modify
+            serde_json::Value::Null => Err(PathErrorKind::Immutable(path[0].clone())),
+            serde_json::Value::Bool(x) => Ok((1, x)),
+            serde_json::Value::Number(x) => Ok((1, x)),
+            serde_json::Value::String(x) => Ok((1, x)),
+            serde_json::Value::Array(x) => Ok((1, x)),
+            serde_json::Value::Object(x) => Ok((1, x)),
+        }
+    }
+
+    fn insert(&mut self, value: Box<dyn Any>) -> Result<(), PathErrorKind> {
+        match self {
+            serde_json::Value::Array(items) => Navigate::insert(items, value),
+            serde_json::Value::Object(map) => Navigate::insert(map, value),
+            _ => Err(PathErrorKind::NotACollection {
+                type_name: std::any::type_name::<serde_json::Value>(),
+            }),
+        }
+    }
+
+    fn remove(&mut self, breadcrumb: &Breadcrumb) -> Result<Box<dyn Any>, PathErrorKind> {
+        match self {
+            serde_json::Value::Array(items) => Navigate::remove(items, breadcrumb),
+            serde_json::Value::Object(map) => Navigate::remove(map, breadcrumb),
+            _ => Err(PathErrorKind::NotACollection {
+                type_name: std::any::type_name::<serde_json::Value>(),
+            }),
+        }
+    }
+
+    fn set_variant(
+        &mut self,
+        variant: &str,
+        value: Option<Box<dyn Any>>,
+    ) -> Result<(), PathErrorKind> {
+        use serde_json::Value;
+
+        *self = match (variant, value) {
+            ("Null", None) => Value::Null,
+            ("Bool", Some(value)) => Value::Bool(downcast(value)?),
+            ("Number", Some(value)) => Value::Number(downcast(value)?),
+            ("String", Some(value)) => Value::String(downcast(value)?),
+            ("Array", Some(value)) => Value::Array(downcast(value)?),
+            ("Object", Some(value)) => Value::Object(downcast(value)?),
+            (variant, value) => {
+                return Err(PathErrorKind::UnknownVariant {
+                    type_name: std::any::type_name::<Value>(),
+                    variant: variant.to_string(),
+                    fields: value.is_some() as usize,
+                })
+            }
+        };
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+//! Render an introspected value as an indented tree, for debugging.
+//!
+//! Unlike `{:#?}`, each line shows the breadcrumbs leading to a value from its
+//! parent, so that the path to any value can be read off the tree:
+//!
+//! ```text
+//! $: Config
+//! ├── .name: String = "svc"
+//! ├── .ports: Vec<u16>
+//! │   ├── [0]: u16 = 80
+//! │   └── … 1 more
+//! └── .mode: Mode
+//!     └── ::Fixed.0: i32 = -1
+//! ```
+//!
+//! Type names are shown without their module paths, where they are known: any
+//! value can be rendered with [`pretty`], which only knows the types of
+//! primitives, strings and `serde_json` values, while [`pretty_typed`] finds
+//! the type of every value through the [`TypeInfo`] of the root. The values of
+//! primitives, strings and `serde_json` numbers are shown; strings and chars
+//! are quoted as by `Debug`.
+
+use crate::{
+    dom::{short_type_name, to_node_with, to_typed_node_with, Leaf, Node, NodeKind},
+    Introspect, Options, TypeInfo,
+};
+use std::fmt::Write;
+
+const RESET: &str = "\x1b[0m";
+const DIM: &str = "\x1b[2m";
+const LABEL: &str = "\x1b[1;36m";
+const STRING: &str = "\x1b[32m";
+const NUMBER: &str = "\x1b[33m";
+const OTHER: &str = "\x1b[35m";
+
+/// Options controlling how a tree is rendered.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct PrettyOptions {
+    /// The depth beyond which values are not shown; the root has depth 0.
+    ///
+    /// The children of a value at this depth are summarized as `… N more`.
+    pub max_depth: Option<usize>,
+
+    /// The number of items of each array, list, set or map which are shown.
+    /// Collections are only recognized where their types are known.
+    ///
+    /// Any further items are summarized as `… N more`.
+    pub max_items: Option<usize>,
+
+    /// Colour the output with ANSI escape codes.
+    pub color: bool,
+
+    /// The options with which the value is introspected.
+    pub introspection: Options,
+}
+
+/// Render the tree of values visited by introspecting `value`, which is empty
+/// if nothing was visited.
+pub fn pretty<T: Introspect + ?Sized>(value: &T, options: &PrettyOptions) -> String {
+    to_node_with(value, &options.introspection)
+        .map_or_else(String::new, |node| render(&node, options))
+}
+
+/// Render the tree of values visited by introspecting `value`, with the type
+/// of each value found through the `TypeInfo` of `T`.
+pub fn pretty_typed<T: Introspect + TypeInfo + ?Sized>(
+    value: &T,
+    options: &PrettyOptions,
+) -> String {
+    to_typed_node_with(value, &options.introspection)
+        .map_or_else(String::new, |node| render(&node, options))
+}
+
+/// Render a tree of nodes.
+pub fn render(node: &Node, options: &PrettyOptions) -> String {
+    let mut out = String::new();
+    Renderer {
+        options,
+        out: &mut out,
+    }
+    .node(node, "", "", 0);
+    out
+}
+
+struct Renderer<'a> {
+    options: &'a PrettyOptions,
+    out: &'a mut String,
+}
+
+impl<'a> Renderer<'a> {
+    fn paint(&mut self, color: &str, text: &str) {
+        if self.options.color && !text.is_empty() {
+            let _ = write!(self.out, "{}{}{}", color, text, RESET);
+        } else {
+            self.out.push_str(text);
+        }
+    }
+
+    // render `node` after `guide`, and its children indented by `indent`
+    fn node(&mut self, node: &Node, guide: &str, indent: &str, depth: usize) {
+        self.paint(DIM, guide);
+        self.paint(LABEL, &node.label);
+        if let Some(type_name) = node.type_name {
+            self.out.push_str(": ");
+            self.paint(DIM, &short_type_name(type_name));
+        }
+        if let NodeKind::Leaf(leaf) = &node.kind {
+            self.out.push_str(" = ");
+            let color = match leaf {
+                Leaf::Unit | Leaf::Bool(_) => OTHER,
+                Leaf::Char(_) | Leaf::String(_) => STRING,
+                Leaf::Signed(_) | Leaf::Unsigned(_) | Leaf::Float(_) => NUMBER,
+            };
+            self.paint(color, &leaf.to_string());
+        }
+        self.out.push('\n');
+
+        let mut shown = node.children.len();
+        if self.options.max_depth.is_some_and(|max| depth >= max) {
+            shown = 0;
+        } else if matches!(node.kind, NodeKind::List | NodeKind::Set | NodeKind::Map) {
+            shown = shown.min(self.options.max_items.unwrap_or(usize::MAX));
+        }
+        let elided = node.children.len() - shown;
+        for (idx, child) in node.children[..shown].iter().enumerate() {
+            let last = idx + 1 == shown && elided == 0;
+            let (guide, child_indent) = if last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            self.node(
+                child,
+                &format!("{}{}", indent, guide),
+                &format!("{}{}", indent, child_indent),
+                depth + 1,
+            );
+        }
+        if elided > 0 {
+            self.paint(DIM, &format!("{}└── … {} more", indent, elided));
+            self.out.push('\n');
+        }
+    }
+}
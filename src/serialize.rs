@@ -0,0 +1,635 @@
+//! Introspect any value which implements `serde::Serialize`, without deriving
+//! `Introspect` for it.
+//!
+//! The value is serialized into a [`serde::Serializer`] which, instead of
+//! producing any output, calls the visitor for each value serialized. The
+//! breadcrumbs are those which `#[derive(Spectacle)]` and the `std`
+//! implementations would produce:
+//!
+//! | Serialized as        | Breadcrumb of each child                             |
+//! |----------------------|------------------------------------------------------|
+//! | struct               | `Field(name)`                                        |
+//! | tuple, tuple struct  | `TupleIndex(n)`                                      |
+//! | newtype struct       | `TupleIndex(0)`                                      |
+//! | sequence             | `Index("0")`, …                                      |
+//! | map                  | `Index(key)`; also `MapKey(key)` with `map_keys`     |
+//! | `Some`               | `Variant("Some")`                                    |
+//! | enum variant         | `Variant(name)`, then as a struct, tuple or newtype  |
+//!
+//! Map keys are given in the style of their `Debug` representation, as for
+//! `std` maps: strings and chars are quoted, and unit variants are given by
+//! name. Only keys which serialize as scalars, unit variants or newtypes of
+//! those are supported; other keys produce an [`Error`].
+//!
+//! The visitor receives a value only where one can be reconstructed from the
+//! serialized form. Primitives are visited as themselves, except that strings
+//! are visited as `String` and byte arrays as `Vec<u8>`. The unit value is
+//! visited as `()`. Every other value is visited as a [`Placeholder`]
+//! describing how it was serialized.
+//!
+//! Values are visited in the order in which they are serialized, so
+//! `Options::stable_order` has no effect.
+
+use crate::{
+    type_info::{Type, TypeKind},
+    Breadcrumb, Breadcrumbs, Introspect, Options, TypeInfo,
+};
+use serde::ser::{self, Impossible, Serialize};
+use std::{any::Any, fmt};
+
+/// Stands in for a value which is not a primitive, whose own type is unknown
+/// because it was visited through its `Serialize` implementation.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Placeholder {
+    None,
+    Some,
+    UnitStruct(&'static str),
+    UnitVariant {
+        name: &'static str,
+        variant: &'static str,
+    },
+    NewtypeStruct(&'static str),
+    NewtypeVariant {
+        name: &'static str,
+        variant: &'static str,
+    },
+    Seq,
+    Tuple,
+    TupleStruct(&'static str),
+    TupleVariant {
+        name: &'static str,
+        variant: &'static str,
+    },
+    Map,
+    Struct(&'static str),
+    StructVariant {
+        name: &'static str,
+        variant: &'static str,
+    },
+}
+
+/// An error raised while serializing a value for introspection.
+///
+/// This is either an error raised by the value's `Serialize` implementation,
+/// or a map key which cannot be represented as a breadcrumb.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+/// Introspect `value` through its `Serialize` implementation.
+pub fn introspect_serialize<T, F>(value: &T, mut visit: F) -> Result<(), Error>
+where
+    T: Serialize + ?Sized,
+    F: FnMut(&Breadcrumbs, &dyn Any),
+{
+    introspect_serialize_with(value, &Options::default(), Breadcrumbs::new(), &mut visit)
+}
+
+/// Introspect `value` through its `Serialize` implementation according to
+/// `options`, from the given breadcrumbs.
+pub fn introspect_serialize_with<T, F>(
+    value: &T,
+    options: &Options,
+    breadcrumbs: Breadcrumbs,
+    mut visit: F,
+) -> Result<(), Error>
+where
+    T: Serialize + ?Sized,
+    F: FnMut(&Breadcrumbs, &dyn Any),
+{
+    value.serialize(Walker {
+        options,
+        breadcrumbs,
+        visit: &mut visit,
+    })
+}
+
+/// Implements `Introspect` for a `Serialize` type, so that it can be
+/// introspected as part of a larger structure, such as a field of a type
+/// which derives `Spectacle`.
+///
+/// The wrapper is visited first, as a newtype struct: its contents are then
+/// visited beneath it, following `TupleIndex(0)`, as by
+/// [`introspect_serialize`]. If serialization fails, introspection of the
+/// contents stops at that point, and the [`Error`] is visited at the
+/// breadcrumbs of the contents.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct Serialized<T>(pub T);
+
+impl<T: Serialize + 'static> Introspect for Serialized<T> {
+    fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, visit: F)
+    where
+        F: FnMut(&Breadcrumbs, &dyn Any),
+    {
+        self.introspect_from_with(&Options::default(), breadcrumbs, visit);
+    }
+
+    fn introspect_from_with<F>(&self, options: &Options, breadcrumbs: Breadcrumbs, mut visit: F)
+    where
+        F: FnMut(&Breadcrumbs, &dyn Any),
+    {
+        visit(&breadcrumbs, self);
+        let mut breadcrumbs = breadcrumbs;
+        breadcrumbs.push_back(Breadcrumb::TupleIndex(0));
+        if let Err(err) =
+            introspect_serialize_with(&self.0, options, breadcrumbs.clone(), &mut visit)
+        {
+            visit(&breadcrumbs, &err);
+        }
+    }
+}
+
+impl<T> TypeInfo for Serialized<T> {
+    fn type_info() -> Type {
+        Type::new::<Self>(TypeKind::Opaque)
+    }
+}
+
+// Visits each value as it is serialized.
+struct Walker<'a> {
+    options: &'a Options,
+    breadcrumbs: Breadcrumbs,
+    visit: &'a mut dyn FnMut(&Breadcrumbs, &dyn Any),
+}
+
+impl<'a> Walker<'a> {
+    fn leaf<T: Any>(self, value: T) -> Result<(), Error> {
+        (self.visit)(&self.breadcrumbs, &value);
+        Ok(())
+    }
+
+    // visit a placeholder, and return the breadcrumbs of its children
+    fn placeholder(&mut self, placeholder: Placeholder) -> Breadcrumbs {
+        (self.visit)(&self.breadcrumbs, &placeholder);
+        let mut breadcrumbs = self.breadcrumbs.clone();
+        match placeholder {
+            Placeholder::Some => breadcrumbs.push_back(Breadcrumb::Variant("Some")),
+            Placeholder::NewtypeVariant { variant, .. }
+            | Placeholder::TupleVariant { variant, .. }
+            | Placeholder::StructVariant { variant, .. } => {
+                breadcrumbs.push_back(Breadcrumb::Variant(variant))
+            }
+            _ => {}
+        }
+        breadcrumbs
+    }
+
+    fn compound(mut self, placeholder: Placeholder) -> Children<'a> {
+        let breadcrumbs = self.placeholder(placeholder);
+        Children {
+            walker: Walker {
+                breadcrumbs,
+                ..self
+            },
+            idx: 0,
+            key: None,
+        }
+    }
+
+    fn child(&mut self, breadcrumb: Breadcrumb) -> Walker<'_> {
+        let mut breadcrumbs = self.breadcrumbs.clone();
+        breadcrumbs.push_back(breadcrumb);
+        Walker {
+            options: self.options,
+            breadcrumbs,
+            visit: &mut *self.visit,
+        }
+    }
+}
+
+macro_rules! serialize_primitives {
+    ($($method:ident($t:ty)),* $(,)?) => {
+        $(
+            fn $method(self, v: $t) -> Result<(), Error> {
+                self.leaf(v)
+            }
+        )*
+    };
+}
+
+impl<'a> ser::Serializer for Walker<'a> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Children<'a>;
+    type SerializeTuple = Children<'a>;
+    type SerializeTupleStruct = Children<'a>;
+    type SerializeTupleVariant = Children<'a>;
+    type SerializeMap = Children<'a>;
+    type SerializeStruct = Children<'a>;
+    type SerializeStructVariant = Children<'a>;
+
+    serialize_primitives!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+    );
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        self.leaf(v.to_string())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+        self.leaf(v.to_vec())
+    }
+
+    fn serialize_none(mut self) -> Result<(), Error> {
+        self.placeholder(Placeholder::None);
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(mut self, value: &T) -> Result<(), Error> {
+        let breadcrumbs = self.placeholder(Placeholder::Some);
+        value.serialize(Walker {
+            breadcrumbs,
+            ..self
+        })
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.leaf(())
+    }
+
+    fn serialize_unit_struct(mut self, name: &'static str) -> Result<(), Error> {
+        self.placeholder(Placeholder::UnitStruct(name));
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        mut self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        self.placeholder(Placeholder::UnitVariant { name, variant });
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        let mut children = self.compound(Placeholder::NewtypeStruct(name));
+        value.serialize(children.walker.child(Breadcrumb::TupleIndex(0)))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        let mut children = self.compound(Placeholder::NewtypeVariant { name, variant });
+        value.serialize(children.walker.child(Breadcrumb::TupleIndex(0)))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Children<'a>, Error> {
+        Ok(self.compound(Placeholder::Seq))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Children<'a>, Error> {
+        Ok(self.compound(Placeholder::Tuple))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<Children<'a>, Error> {
+        Ok(self.compound(Placeholder::TupleStruct(name)))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Children<'a>, Error> {
+        Ok(self.compound(Placeholder::TupleVariant { name, variant }))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Children<'a>, Error> {
+        Ok(self.compound(Placeholder::Map))
+    }
+
+    fn serialize_struct(self, name: &'static str, _len: usize) -> Result<Children<'a>, Error> {
+        Ok(self.compound(Placeholder::Struct(name)))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Children<'a>, Error> {
+        Ok(self.compound(Placeholder::StructVariant { name, variant }))
+    }
+}
+
+// Visits the children of a compound value.
+struct Children<'a> {
+    // the walker positioned at the compound value's children
+    walker: Walker<'a>,
+    // the index of the next positional child
+    idx: usize,
+    // the key of the map entry whose value is next
+    key: Option<String>,
+}
+
+impl<'a> Children<'a> {
+    fn item<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let idx = self.idx;
+        self.idx += 1;
+        value.serialize(self.walker.child(Breadcrumb::Index(format!("{}", idx))))
+    }
+
+    fn tuple_item<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let idx = self.idx;
+        self.idx += 1;
+        value.serialize(self.walker.child(Breadcrumb::TupleIndex(idx)))
+    }
+
+    fn field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        value.serialize(self.walker.child(Breadcrumb::Field(key)))
+    }
+}
+
+impl<'a> ser::SerializeSeq for Children<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.item(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeTuple for Children<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.tuple_item(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeTupleStruct for Children<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.tuple_item(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeTupleVariant for Children<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.tuple_item(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeMap for Children<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        let name = key.serialize(KeyName)?;
+        if self.walker.options.map_keys {
+            key.serialize(self.walker.child(Breadcrumb::MapKey(name.clone())))?;
+        }
+        self.key = Some(name);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error("map value serialized without a key".to_string()))?;
+        value.serialize(self.walker.child(Breadcrumb::Index(key)))
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeStruct for Children<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeStructVariant for Children<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+// Names a map key in the style of its `Debug` representation.
+struct KeyName;
+
+fn unsupported_key<T>() -> Result<T, Error> {
+    Err(Error(
+        "map keys must serialize as scalars, unit variants or newtypes".to_string(),
+    ))
+}
+
+macro_rules! name_keys {
+    ($($method:ident($t:ty) => $fmt:literal),* $(,)?) => {
+        $(
+            fn $method(self, v: $t) -> Result<String, Error> {
+                Ok(format!($fmt, v))
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for KeyName {
+    type Ok = String;
+    type Error = Error;
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    name_keys!(
+        serialize_bool(bool) => "{}",
+        serialize_i8(i8) => "{}",
+        serialize_i16(i16) => "{}",
+        serialize_i32(i32) => "{}",
+        serialize_i64(i64) => "{}",
+        serialize_i128(i128) => "{}",
+        serialize_u8(u8) => "{}",
+        serialize_u16(u16) => "{}",
+        serialize_u32(u32) => "{}",
+        serialize_u64(u64) => "{}",
+        serialize_u128(u128) => "{}",
+        serialize_f32(f32) => "{:?}",
+        serialize_f64(f64) => "{:?}",
+        serialize_char(char) => "{:?}",
+        serialize_str(&str) => "{:?}",
+        serialize_bytes(&[u8]) => "{:?}",
+    );
+
+    fn serialize_none(self) -> Result<String, Error> {
+        Ok("None".to_string())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<String, Error> {
+        Ok(format!("Some({})", value.serialize(KeyName)?))
+    }
+
+    fn serialize_unit(self) -> Result<String, Error> {
+        Ok("()".to_string())
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<String, Error> {
+        Ok(name.to_string())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String, Error> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<String, Error> {
+        Ok(format!("{}({})", name, value.serialize(KeyName)?))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<String, Error> {
+        Ok(format!("{}({})", variant, value.serialize(KeyName)?))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        unsupported_key()
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        unsupported_key()
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        unsupported_key()
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        unsupported_key()
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        unsupported_key()
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        unsupported_key()
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        unsupported_key()
+    }
+}
@@ -0,0 +1,547 @@
+//! Static descriptions of the shapes of types.
+//!
+//! `Introspect` can only describe values which exist. [`TypeInfo`] describes
+//! the shape which every value of a type shares: the fields of a struct, the
+//! variants of an enum, the items of a collection, and so on. It is
+//! implemented for every type which implements `Introspect` in this crate,
+//! and `#[derive(Spectacle)]` implements it along with `Introspect`. The
+//! type of a field which does not implement `TypeInfo` is described as
+//! [`TypeKind::Opaque`].
+//!
+//! Types may be recursive, so the types of children are [`TypeRef`]s, which
+//! produce their [`Type`] only on demand.
+//!
+//! [`paths`] enumerates the breadcrumbs at which `Introspect` may visit
+//! values within a type, without needing a value of that type.
+
+use crate::{Breadcrumb, Breadcrumbs};
+use std::{any::Any, fmt, marker::PhantomData};
+
+/// A type whose shape can be described without an instance.
+pub trait TypeInfo {
+    /// Describe the shape of `Self`.
+    fn type_info() -> Type;
+}
+
+/// The shape of a type.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Type {
+    /// The name of the type, as given by `std::any::type_name`.
+    pub name: &'static str,
+    /// The structure of the type.
+    pub kind: TypeKind,
+    /// The type arguments with which the type was instantiated, such as `T`
+    /// in `Vec<T>`. Lifetime and const arguments are not included.
+    pub generics: Vec<TypeRef>,
+    /// Names the active variant of a value of an enum, if the type can.
+    pub active_variant: Option<ActiveVariant>,
+}
+
+impl Type {
+    /// Describe `T` as having the given kind.
+    pub fn new<T: ?Sized>(kind: TypeKind) -> Type {
+        Type {
+            name: std::any::type_name::<T>(),
+            kind,
+            generics: Vec::new(),
+            active_variant: None,
+        }
+    }
+
+    /// Set the type arguments with which the type was instantiated.
+    pub fn with_generics(mut self, generics: Vec<TypeRef>) -> Type {
+        self.generics = generics;
+        self
+    }
+
+    /// Set the function which names the active variant of a value of this enum.
+    pub fn with_active_variant(mut self, active_variant: ActiveVariant) -> Type {
+        self.active_variant = Some(active_variant);
+        self
+    }
+
+    /// The number of items of a tuple or tuple struct, or `None` for other types.
+    pub fn arity(&self) -> Option<usize> {
+        match &self.kind {
+            TypeKind::Tuple(items) => Some(items.len()),
+            TypeKind::Struct(Fields::Unnamed(fields)) => Some(fields.len()),
+            _ => None,
+        }
+    }
+
+    /// The breadcrumbs which lead from a value of this type to each child it
+    /// may have, and the type of that child.
+    ///
+    /// The indices of collections are unknown, so are given as `*`:
+    /// `Breadcrumb::Index("*")` for lists, arrays and map values, and
+    /// `Breadcrumb::SetMember("*")` for sets. Enum variants lead to their
+    /// fields through a `Breadcrumb::Variant`. Any JSON value has no children
+    /// which can be known in advance.
+    pub fn children(&self) -> Vec<(Vec<Breadcrumb>, TypeRef)> {
+        let any = || "*".to_string();
+        match &self.kind {
+            TypeKind::Struct(fields) => fields.children(None),
+            TypeKind::Enum(variants) => variants
+                .iter()
+                .flat_map(|variant| variant.fields.children(Some(variant.name)))
+                .collect(),
+            TypeKind::Tuple(items) => items
+                .iter()
+                .enumerate()
+                .map(|(idx, item)| (vec![Breadcrumb::TupleIndex(idx)], *item))
+                .collect(),
+            TypeKind::Array(item, _) | TypeKind::List(item) | TypeKind::Map(_, item) => {
+                vec![(vec![Breadcrumb::Index(any())], *item)]
+            }
+            TypeKind::Set(item) => vec![(vec![Breadcrumb::SetMember(any())], *item)],
+            TypeKind::Option(some) => vec![(vec![Breadcrumb::Variant("Some")], *some)],
+            TypeKind::Result(ok, err) => vec![
+                (vec![Breadcrumb::Variant("Ok")], *ok),
+                (vec![Breadcrumb::Variant("Err")], *err),
+            ],
+            TypeKind::Primitive(_) | TypeKind::Json | TypeKind::Opaque => Vec::new(),
+        }
+    }
+}
+
+/// Enumerate the breadcrumbs at which a value of type `T` may visit itself and
+/// its descendants, paired with the type visited there, in the order in which
+/// they would be visited.
+///
+/// Collection indices are given as `*`, as by [`Type::children`]. A recursive
+/// type is not expanded within itself, so the enumeration is finite: the path
+/// at which a type recurs is included, but not the paths beneath it.
+pub fn paths<T: TypeInfo + ?Sized>() -> Vec<(Breadcrumbs, Type)> {
+    let mut paths = Vec::new();
+    let mut ancestors = Vec::new();
+    collect_paths(
+        T::type_info(),
+        Breadcrumbs::new(),
+        &mut ancestors,
+        &mut paths,
+    );
+    paths
+}
+
+fn collect_paths(
+    ty: Type,
+    breadcrumbs: Breadcrumbs,
+    ancestors: &mut Vec<&'static str>,
+    paths: &mut Vec<(Breadcrumbs, Type)>,
+) {
+    let recurs = ancestors.contains(&ty.name);
+    let children = if recurs { Vec::new() } else { ty.children() };
+    ancestors.push(ty.name);
+    paths.push((breadcrumbs.clone(), ty));
+    for (steps, child) in children {
+        let mut breadcrumbs = breadcrumbs.clone();
+        breadcrumbs.extend(steps);
+        collect_paths(child.get(), breadcrumbs, ancestors, paths);
+    }
+    ancestors.pop();
+}
+
+/// A lazily evaluated reference to the shape of a type.
+///
+/// Two references are equal when they refer to types of the same name.
+#[derive(Clone, Copy)]
+pub struct TypeRef(fn() -> Type);
+
+impl TypeRef {
+    /// Refer to the shape of `T`.
+    pub fn of<T: TypeInfo + ?Sized>() -> TypeRef {
+        TypeRef(T::type_info)
+    }
+
+    /// Describe the shape of the referenced type.
+    pub fn get(self) -> Type {
+        (self.0)()
+    }
+
+    /// The name of the referenced type.
+    pub fn name(self) -> &'static str {
+        self.get().name
+    }
+}
+
+impl PartialEq for TypeRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name()
+    }
+}
+
+/// References display only the name of their type, so that recursive types
+/// can be displayed.
+impl fmt::Debug for TypeRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("TypeRef").field(&self.name()).finish()
+    }
+}
+
+/// Names the active variant of a value of an enum, which is given as `Any`,
+/// or returns `None` if it is not a value of that enum.
+///
+/// A type has only one such function, so any two are equal.
+#[derive(Clone, Copy)]
+pub struct ActiveVariant(pub fn(&dyn Any) -> Option<&'static str>);
+
+impl ActiveVariant {
+    /// The name of the active variant of `value`.
+    pub fn of(self, value: &dyn Any) -> Option<&'static str> {
+        (self.0)(value)
+    }
+}
+
+impl PartialEq for ActiveVariant {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for ActiveVariant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ActiveVariant")
+    }
+}
+
+// The types of the fields of a derived type are referred to through
+// `FieldType`, so that a field whose type does not implement `TypeInfo` is
+// described as opaque rather than failing to compile: method resolution
+// prefers `DescribedField`, which takes `FieldType` by reference, over
+// `OpaqueField`, which takes it by a reference to a reference.
+
+#[doc(hidden)]
+pub struct FieldType<T: ?Sized>(PhantomData<fn() -> *const T>);
+
+impl<T: ?Sized> FieldType<T> {
+    #[doc(hidden)]
+    pub fn new() -> FieldType<T> {
+        FieldType(PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait DescribedField {
+    fn type_ref(&self) -> TypeRef;
+}
+
+impl<T: TypeInfo + ?Sized> DescribedField for FieldType<T> {
+    fn type_ref(&self) -> TypeRef {
+        TypeRef::of::<T>()
+    }
+}
+
+#[doc(hidden)]
+pub trait OpaqueField {
+    fn type_ref(&self) -> TypeRef;
+}
+
+impl<T: ?Sized> OpaqueField for &FieldType<T> {
+    fn type_ref(&self) -> TypeRef {
+        TypeRef(opaque::<T>)
+    }
+}
+
+fn opaque<T: ?Sized>() -> Type {
+    Type::new::<T>(TypeKind::Opaque)
+}
+
+/// The structure of a type.
+#[derive(Clone, PartialEq, Debug)]
+pub enum TypeKind {
+    /// A type with no children.
+    Primitive(Primitive),
+    /// A struct.
+    Struct(Fields),
+    /// An enum.
+    Enum(Vec<Variant>),
+    /// A tuple. The unit type is the empty tuple.
+    Tuple(Vec<TypeRef>),
+    /// A fixed-size array.
+    Array(TypeRef, usize),
+    /// A slice or a sequential collection, such as `Vec`.
+    List(TypeRef),
+    /// A set.
+    Set(TypeRef),
+    /// A map, from key to value.
+    Map(TypeRef, TypeRef),
+    /// An `Option`.
+    Option(TypeRef),
+    /// A `Result`, of the success and error types.
+    Result(TypeRef, TypeRef),
+    /// Any JSON value, such as a `serde_json::Value`.
+    Json,
+    /// A type whose structure is not described.
+    Opaque,
+}
+
+/// A type with no children.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Primitive {
+    Bool,
+    Char,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    Usize,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    Isize,
+    F32,
+    F64,
+    /// A `String` or `str`.
+    String,
+    /// Any JSON number, such as a `serde_json::Number`.
+    Number,
+}
+
+/// The fields of a struct or enum variant.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Fields {
+    Unit,
+    Named(Vec<Field>),
+    Unnamed(Vec<TypeRef>),
+}
+
+impl Fields {
+    /// The number of fields.
+    pub fn len(&self) -> usize {
+        match self {
+            Fields::Unit => 0,
+            Fields::Named(fields) => fields.len(),
+            Fields::Unnamed(fields) => fields.len(),
+        }
+    }
+
+    /// True if there are no fields.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // the breadcrumbs leading to each field, within the named variant if any
+    fn children(&self, variant: Option<&'static str>) -> Vec<(Vec<Breadcrumb>, TypeRef)> {
+        let step = |breadcrumb| match variant {
+            Some(variant) => vec![Breadcrumb::Variant(variant), breadcrumb],
+            None => vec![breadcrumb],
+        };
+        match self {
+            Fields::Unit => Vec::new(),
+            Fields::Named(fields) => fields
+                .iter()
+                .map(|field| (step(Breadcrumb::Field(field.name)), field.ty))
+                .collect(),
+            Fields::Unnamed(fields) => fields
+                .iter()
+                .enumerate()
+                .map(|(idx, ty)| (step(Breadcrumb::TupleIndex(idx)), *ty))
+                .collect(),
+        }
+    }
+}
+
+/// A named field.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Field {
+    pub name: &'static str,
+    pub ty: TypeRef,
+}
+
+/// A variant of an enum.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Variant {
+    pub name: &'static str,
+    pub fields: Fields,
+}
+
+impl<T> TypeInfo for &T
+where
+    T: TypeInfo + ?Sized,
+{
+    fn type_info() -> Type {
+        T::type_info()
+    }
+}
+
+impl<T: TypeInfo> TypeInfo for [T] {
+    fn type_info() -> Type {
+        Type::new::<Self>(TypeKind::List(TypeRef::of::<T>()))
+            .with_generics(vec![TypeRef::of::<T>()])
+    }
+}
+
+macro_rules! impl_primitive {
+    ($($t:ty => $p:ident),+ $(,)?) => {
+        $(
+            impl TypeInfo for $t {
+                fn type_info() -> Type {
+                    Type::new::<Self>(TypeKind::Primitive(Primitive::$p))
+                }
+            }
+        )+
+    };
+}
+
+impl_primitive!(
+    bool => Bool,
+    char => Char,
+    u8 => U8,
+    u16 => U16,
+    u32 => U32,
+    u64 => U64,
+    u128 => U128,
+    usize => Usize,
+    i8 => I8,
+    i16 => I16,
+    i32 => I32,
+    i64 => I64,
+    i128 => I128,
+    isize => Isize,
+    f32 => F32,
+    f64 => F64,
+    String => String,
+    str => String,
+);
+
+impl<T: TypeInfo, const N: usize> TypeInfo for [T; N] {
+    fn type_info() -> Type {
+        Type::new::<Self>(TypeKind::Array(TypeRef::of::<T>(), N))
+            .with_generics(vec![TypeRef::of::<T>()])
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($t:ident),*) => {
+        impl<$($t: TypeInfo),*> TypeInfo for ($($t,)*) {
+            fn type_info() -> Type {
+                let items = vec![$(TypeRef::of::<$t>()),*];
+                Type::new::<Self>(TypeKind::Tuple(items.clone())).with_generics(items)
+            }
+        }
+    };
+}
+
+// implement for a tuple of every suffix of the list of type parameters
+macro_rules! impl_tuples {
+    () => {
+        impl_tuple!();
+    };
+    ($t:ident $(, $ts:ident)*) => {
+        impl_tuple!($t $(, $ts)*);
+        impl_tuples!($($ts),*);
+    };
+}
+
+impl_tuples!(
+    T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16, T17, T18, T19, T20,
+    T21, T22, T23, T24, T25, T26, T27, T28, T29, T30, T31
+);
+
+impl<T: TypeInfo> TypeInfo for Option<T> {
+    fn type_info() -> Type {
+        Type::new::<Self>(TypeKind::Option(TypeRef::of::<T>()))
+            .with_generics(vec![TypeRef::of::<T>()])
+    }
+}
+
+impl<T: TypeInfo, E: TypeInfo> TypeInfo for Result<T, E> {
+    fn type_info() -> Type {
+        let (ok, err) = (TypeRef::of::<T>(), TypeRef::of::<E>());
+        Type::new::<Self>(TypeKind::Result(ok, err)).with_generics(vec![ok, err])
+    }
+}
+
+macro_rules! impl_collection {
+    ($kind:ident: $($t:ident)::+) => {
+        #[cfg(feature = "collections")]
+        impl<T: TypeInfo> TypeInfo for $($t)::+<T> {
+            fn type_info() -> Type {
+                Type::new::<Self>(TypeKind::$kind(TypeRef::of::<T>()))
+                    .with_generics(vec![TypeRef::of::<T>()])
+            }
+        }
+    };
+}
+
+impl_collection!(List: Vec);
+impl_collection!(List: std::collections::VecDeque);
+impl_collection!(List: std::collections::LinkedList);
+impl_collection!(Set: std::collections::BTreeSet);
+impl_collection!(Set: std::collections::BinaryHeap);
+impl_collection!(Set: std::collections::HashSet);
+
+macro_rules! impl_map {
+    ($($t:ident)::+) => {
+        #[cfg(feature = "collections")]
+        impl<K: TypeInfo, V: TypeInfo> TypeInfo for $($t)::+<K, V> {
+            fn type_info() -> Type {
+                let (k, v) = (TypeRef::of::<K>(), TypeRef::of::<V>());
+                Type::new::<Self>(TypeKind::Map(k, v)).with_generics(vec![k, v])
+            }
+        }
+    };
+}
+
+impl_map!(std::collections::HashMap);
+impl_map!(std::collections::BTreeMap);
+
+#[cfg(feature = "serde-json")]
+mod serde_json_impls {
+    use super::*;
+
+    impl TypeInfo for serde_json::Value {
+        fn type_info() -> Type {
+            Type::new::<Self>(TypeKind::Json)
+        }
+    }
+
+    impl TypeInfo for serde_json::Map<String, serde_json::Value> {
+        fn type_info() -> Type {
+            Type::new::<Self>(TypeKind::Map(
+                TypeRef::of::<String>(),
+                TypeRef::of::<serde_json::Value>(),
+            ))
+        }
+    }
+
+    impl TypeInfo for Box<serde_json::value::RawValue> {
+        fn type_info() -> Type {
+            Type::new::<Self>(TypeKind::Json)
+        }
+    }
+
+    impl TypeInfo for serde_json::Number {
+        fn type_info() -> Type {
+            Type::new::<Self>(TypeKind::Primitive(Primitive::Number))
+        }
+    }
+
+    impl TypeInfo for serde_json::error::Category {
+        fn type_info() -> Type {
+            Type::new::<Self>(TypeKind::Opaque)
+        }
+    }
+
+    impl TypeInfo for serde_json::Error {
+        fn type_info() -> Type {
+            Type::new::<Self>(TypeKind::Struct(Fields::Named(vec![
+                Field {
+                    name: "category",
+                    ty: TypeRef::of::<serde_json::error::Category>(),
+                },
+                Field {
+                    name: "line",
+                    ty: TypeRef::of::<usize>(),
+                },
+                Field {
+                    name: "column",
+                    ty: TypeRef::of::<usize>(),
+                },
+            ])))
+        }
+    }
+}
@@ -0,0 +1,103 @@
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn spectacle(args: &[&str], input: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_spectacle"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+const LINES: &str = r#"{"a": [1, {"b": "x"}]}
+{"a": [], "c": null}
+"#;
+
+#[test]
+fn paths() {
+    assert_eq!(
+        spectacle(&["paths"], LINES),
+        "$\tobject\n$.a\tarray\n$.a[0]\tinteger\t1\n$.a[1]\tobject\n$.a[1].b\tstring\t\"x\"\n\
+         $\tobject\n$.a\tarray\n$.c\tnull\tnull\n"
+    );
+}
+
+#[test]
+fn filter() {
+    assert_eq!(
+        spectacle(&["filter", "$.a[*]"], LINES),
+        "$.a[0]\t1\n$.a[1]\t{\"b\":\"x\"}\n"
+    );
+}
+
+#[test]
+fn filter_wildcards() {
+    // `*` stays within a segment, while `**` crosses them
+    assert_eq!(
+        spectacle(&["filter", "$.*"], LINES),
+        "$.a\t[1,{\"b\":\"x\"}]\n$.a\t[]\n$.c\tnull\n"
+    );
+    assert_eq!(
+        spectacle(&["filter", "$.a**"], "{\"a\": [{\"b\": 1}]}"),
+        "$.a\t[{\"b\":1}]\n$.a[0]\t{\"b\":1}\n$.a[0].b\t1\n"
+    );
+}
+
+#[test]
+fn filter_duplicate_keys() {
+    // the last value of a duplicate key replaces the matches within the first
+    assert_eq!(
+        spectacle(&["filter", "**"], "{\"a\": [1], \"a\": {\"x\": 2}}"),
+        "$\t{\"a\":{\"x\":2}}\n$.a\t{\"x\":2}\n$.a.x\t2\n"
+    );
+}
+
+#[test]
+fn closed_output() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_spectacle"))
+        .arg("paths")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    drop(child.stdout.take());
+    // the output is larger than a pipe's buffer, so cannot all be written;
+    // writing the input may fail too, if it is not all read
+    let _ = child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(LINES.repeat(10_000).as_bytes());
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "");
+}
+
+#[test]
+fn summary() {
+    assert_eq!(
+        spectacle(&["summary", "-"], LINES),
+        "$: 2 (object: 2)\n$.a: 2 (array: 2)\n$.a[*]: 2 (integer: 1, object: 1)\n\
+         $.a[*].b: 1 (string: 1)\n$.c: 1 (null: 1)\n"
+    );
+}
+
+#[test]
+fn types() {
+    let types = spectacle(&["types", "--root", "Line"], LINES);
+    assert!(types.contains("pub struct Line {"));
+    assert!(types.contains("pub a: Vec<AItem>,"));
+}
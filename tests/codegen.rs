@@ -0,0 +1,164 @@
+#![cfg(feature = "serde-json")]
+
+use serde_json::json;
+use spectacle::json::{codegen::Generator, infer::infer};
+
+#[test]
+fn structs_options_enums_renames_and_maps() {
+    let documents = vec![
+        json!({
+            "userName": "a",
+            "type": 1,
+            "tags": ["x"],
+            "counts": {"2021-01-01": 1, "2021-01-02": 2},
+            "extra": null,
+        }),
+        json!({
+            "userName": "b",
+            "type": "one",
+            "tags": [],
+            "counts": {},
+        }),
+    ];
+    let schema = infer(&documents);
+    let source = Generator::new("Document")
+        .derives(vec!["Debug", "Deserialize"])
+        .generate(&schema);
+    assert_eq!(
+        source,
+        r#"use std::collections::HashMap;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct Document {
+    pub counts: HashMap<String, i64>,
+    #[serde(default)]
+    pub extra: Option<serde_json::Value>,
+    pub tags: Vec<String>,
+    pub r#type: Type,
+    #[serde(rename = "userName")]
+    pub user_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Type {
+    Integer(i64),
+    String(String),
+}
+"#
+    );
+}
+
+#[test]
+fn non_object_root() {
+    let schema = infer(vec![&json!([{"a": 1}]), &json!(null)]);
+    let source = Generator::new("Root")
+        .derives(Vec::<String>::new())
+        .generate(&schema);
+    assert_eq!(
+        source,
+        "
+pub type Root = Option<Vec<RootItem>>;
+
+pub struct RootItem {
+    pub a: i64,
+}
+"
+    );
+
+    // a nested type is not given the root's name
+    let schema = infer(vec![&json!([{"root": {"b": true}}])]);
+    let source = Generator::new("Root")
+        .derives(vec!["Serialize"])
+        .generate(&schema);
+    assert_eq!(
+        source,
+        r#"use serde::Serialize;
+
+pub type Root = Vec<RootItem>;
+
+#[derive(Serialize)]
+pub struct RootItem {
+    pub root: Root2,
+}
+
+#[derive(Serialize)]
+pub struct Root2 {
+    pub b: bool,
+}
+"#
+    );
+}
+
+#[test]
+fn string_formats() {
+    let documents = vec![
+        json!({"day": "2021-03-04", "count": "12"}),
+        json!({"day": "2021-03-05", "count": "13"}),
+    ];
+    let schema = infer(&documents);
+    let generator = Generator::new("Row").derives(vec!["Debug"]);
+    assert_eq!(
+        generator.clone().generate(&schema),
+        r#"
+#[derive(Debug)]
+pub struct Row {
+    /// Format: integer.
+    pub count: String,
+    /// Format: date; consider `chrono::NaiveDate`.
+    pub day: String,
+}
+"#
+    );
+    assert_eq!(
+        generator.format_types(true).generate(&schema),
+        r#"
+#[derive(Debug)]
+pub struct Row {
+    pub count: String,
+    pub day: chrono::NaiveDate,
+}
+"#
+    );
+
+    // format types do not implement `Introspect`
+    let source = Generator::new("Row").format_types(true).generate(&schema);
+    assert!(source.contains("#[derive(Debug, Clone, Deserialize, Serialize)]\n"));
+    assert!(!source.contains("Spectacle"));
+}
+
+#[test]
+fn number_types() {
+    let documents = vec![
+        json!({"age": 30, "id": 18446744073709551615u64, "ratio": 0.5, "total": 1}),
+        json!({"age": 41, "id": 1, "ratio": 1, "total": 9007199254740993u64}),
+        json!({"age": 7, "id": 2, "ratio": 0.25, "total": 0.5}),
+    ];
+    let schema = infer(&documents);
+    let generator = Generator::new("Row").derives(Vec::<String>::new());
+    assert_eq!(
+        generator.clone().generate(&schema),
+        r#"
+pub struct Row {
+    pub age: i64,
+    pub id: u64,
+    pub ratio: f64,
+    /// Some integers observed cannot be represented exactly as floats.
+    pub total: f64,
+}
+"#
+    );
+    assert_eq!(
+        generator.narrow_numbers(true).generate(&schema),
+        r#"
+pub struct Row {
+    pub age: u8,
+    pub id: u64,
+    pub ratio: f32,
+    /// Some integers observed cannot be represented exactly as floats.
+    pub total: f64,
+}
+"#
+    );
+}
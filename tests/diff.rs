@@ -0,0 +1,138 @@
+use spectacle::{
+    diff::{diff, diff_typed, Change},
+    Spectacle,
+};
+use std::collections::BTreeMap;
+
+#[derive(Spectacle)]
+struct Service {
+    name: String,
+    ports: Vec<u16>,
+    mode: Mode,
+    limits: BTreeMap<String, u32>,
+}
+
+#[derive(Spectacle)]
+#[allow(dead_code)]
+enum Mode {
+    Off,
+    Fixed(i32),
+    Scaled(i32),
+}
+
+fn service() -> Service {
+    let mut limits = BTreeMap::new();
+    limits.insert("cpu".to_string(), 2);
+    limits.insert("memory".to_string(), 512);
+    Service {
+        name: "svc".to_string(),
+        ports: vec![80, 443],
+        mode: Mode::Fixed(-1),
+        limits,
+    }
+}
+
+#[test]
+fn identical() {
+    assert!(diff_typed(&service(), &service()).is_empty());
+    assert!(diff(&service(), &service()).is_empty());
+}
+
+#[test]
+fn differences() {
+    let old = service();
+    let mut new = service();
+    new.name = "api".to_string();
+    new.ports.push(8080);
+    new.mode = Mode::Scaled(-1);
+    new.limits.remove("cpu");
+    new.limits.insert("memory".to_string(), 1024);
+
+    let diff = diff_typed(&old, &new);
+    assert_eq!(
+        diff.to_string(),
+        r#"~ $.name              "svc" -> "api"
++ $.ports[2]          u16 = 8080
+~ $.mode              Mode::Fixed -> Mode::Scaled
+- $.mode::Fixed.0     i32 = -1
++ $.mode::Scaled.0    i32 = -1
+- $.limits["cpu"]     u32 = 2
+~ $.limits["memory"]  512 -> 1024
+"#
+    );
+    assert!(matches!(diff.differences[1].change, Change::Added(_)));
+    assert_eq!(
+        diff.differences[0].to_string(),
+        r#"~ $.name "svc" -> "api""#
+    );
+}
+
+#[test]
+fn unit_variants() {
+    let old = Service {
+        mode: Mode::Off,
+        ..service()
+    };
+    let new = Service {
+        mode: Mode::Fixed(1),
+        ..service()
+    };
+    assert_eq!(
+        diff_typed(&old, &new).to_string(),
+        "~ $.mode           Mode::Off -> Mode::Fixed\n+ $.mode::Fixed.0  i32 = 1\n"
+    );
+
+    // only the variant distinguishes unit variants
+    #[derive(Spectacle)]
+    enum Power {
+        Off,
+        On,
+    }
+    assert_eq!(
+        diff_typed(&Power::Off, &Power::On).to_string(),
+        "~ $  Power::Off -> Power::On\n"
+    );
+    assert!(diff_typed(&Power::On, &Power::On).is_empty());
+    // without `TypeInfo`, the variant is not known
+    assert!(diff(&Power::Off, &Power::On).is_empty());
+}
+
+#[test]
+fn untyped() {
+    // without `TypeInfo`, only the values beneath variants are compared
+    let old = service();
+    let mut new = service();
+    new.ports.push(8080);
+    new.mode = Mode::Scaled(-1);
+    assert_eq!(
+        diff(&old, &new).to_string(),
+        r#"+ $.ports[2]        u16 = 8080
+- $.mode::Fixed.0   i32 = -1
++ $.mode::Scaled.0  i32 = -1
+"#
+    );
+}
+
+#[cfg(feature = "serde-json")]
+#[test]
+fn json_values() {
+    use serde_json::json;
+
+    let old = json!({"a": 1, "b": [true], "c": null});
+    let new = json!({"a": 1.5, "b": [false, null], "d": {}});
+    let lines: Vec<String> = diff(&old, &new)
+        .differences
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            "~ $::Object[a]::Number 1 -> 1.5",
+            "~ $::Object[b]::Array[0]::Bool true -> false",
+            "+ $::Object[b]::Array[1] Value",
+            "- $::Object[c] Value",
+            "+ $::Object[d] Value",
+        ]
+    );
+}
@@ -0,0 +1,184 @@
+use spectacle::{
+    dom::{to_node, to_typed_node, Leaf, Node, NodeKind},
+    Breadcrumbs, Introspect, Spectacle,
+};
+use std::any::Any;
+
+#[derive(Spectacle)]
+struct Config {
+    name: String,
+    retries: Option<u8>,
+    ports: Vec<u16>,
+    mode: Mode,
+}
+
+#[derive(Spectacle)]
+#[allow(dead_code)]
+enum Mode {
+    Off,
+    Fixed(i32),
+}
+
+fn outline(node: &Node, depth: usize, lines: &mut Vec<String>) {
+    let kind = match &node.kind {
+        NodeKind::Leaf(leaf) => format!("{:?}", leaf),
+        kind => format!("{:?}", kind),
+    };
+    lines.push(format!(
+        "{}{} {} {}",
+        "  ".repeat(depth),
+        node.label,
+        node.type_name.unwrap_or("?"),
+        kind
+    ));
+    for child in &node.children {
+        outline(child, depth + 1, lines);
+    }
+}
+
+#[test]
+fn tree_of_derived_type() {
+    let config = Config {
+        name: "svc".to_string(),
+        retries: Some(3),
+        ports: vec![80, 443],
+        mode: Mode::Fixed(-1),
+    };
+    let node = to_typed_node(&config).unwrap();
+    assert_eq!(node.children[3].variant, Some("Fixed"));
+    let mut lines = Vec::new();
+    outline(&node, 0, &mut lines);
+    assert_eq!(
+        lines,
+        vec![
+            "$ dom::Config Struct",
+            "  .name alloc::string::String String(\"svc\")",
+            "  .retries core::option::Option<u8> Option",
+            "    ::Some u8 Unsigned(3)",
+            "  .ports alloc::vec::Vec<u16> List",
+            "    [0] u16 Unsigned(80)",
+            "    [1] u16 Unsigned(443)",
+            "  .mode dom::Mode Enum",
+            "    ::Fixed.0 i32 Signed(-1)",
+        ]
+    );
+}
+
+#[test]
+fn leaves() {
+    let node = to_typed_node(&(true, 'c', 1.5f32, ())).unwrap();
+    let leaves: Vec<&NodeKind> = node.children.iter().map(|child| &child.kind).collect();
+    assert_eq!(
+        leaves,
+        vec![
+            &NodeKind::Leaf(Leaf::Bool(true)),
+            &NodeKind::Leaf(Leaf::Char('c')),
+            &NodeKind::Leaf(Leaf::Float(1.5)),
+            &NodeKind::Leaf(Leaf::Unit),
+        ]
+    );
+    assert_eq!(node.kind, NodeKind::Tuple);
+}
+
+#[test]
+fn untyped_tree() {
+    // without `TypeInfo`, only primitives and strings are recognized
+    let mut lines = Vec::new();
+    outline(
+        &to_node(&(Some(1u8), "x".to_string())).unwrap(),
+        0,
+        &mut lines,
+    );
+    assert_eq!(
+        lines,
+        vec![
+            "$ ? Opaque",
+            "  .0 ? Opaque",
+            "    ::Some u8 Unsigned(1)",
+            "  .1 alloc::string::String String(\"x\")",
+        ]
+    );
+}
+
+// visits nothing, or visits the root twice
+struct Unusual(usize);
+
+impl Introspect for Unusual {
+    fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, mut visit: F)
+    where
+        F: FnMut(&Breadcrumbs, &dyn Any),
+    {
+        for _ in 0..self.0 {
+            visit(&breadcrumbs, &());
+        }
+    }
+}
+
+#[test]
+fn unusual_roots() {
+    assert_eq!(to_node(&Unusual(0)), None);
+    let node = to_node(&Unusual(2)).unwrap();
+    assert_eq!(node.kind, NodeKind::Leaf(Leaf::Unit));
+    assert!(node.children.is_empty());
+}
+
+#[cfg(feature = "serde-json")]
+#[test]
+fn json_export() {
+    use serde_json::json;
+
+    let value = json!({"a": [1, "x"]});
+    assert_eq!(
+        to_node(&value).unwrap().to_json(),
+        json!({
+            "label": "$",
+            "type": "serde_json::value::Value",
+            "kind": "json",
+            "children": [{
+                "label": "::Object",
+                "type": "serde_json::map::Map<alloc::string::String, serde_json::value::Value>",
+                "kind": "map",
+                "children": [{
+                    "label": "[a]",
+                    "type": "serde_json::value::Value",
+                    "kind": "json",
+                    "children": [{
+                        "label": "::Array",
+                        "type": "alloc::vec::Vec<serde_json::value::Value>",
+                        "kind": "list",
+                        "children": [
+                            {
+                                "label": "[0]",
+                                "type": "serde_json::value::Value",
+                                "kind": "json",
+                                "children": [{
+                                    "label": "::Number",
+                                    "type": "serde_json::number::Number",
+                                    "kind": "leaf",
+                                    "value": 1,
+                                    "children": [{
+                                        "label": "::PosInt",
+                                        "type": "u64",
+                                        "kind": "leaf",
+                                        "value": 1,
+                                    }],
+                                }],
+                            },
+                            {
+                                "label": "[1]",
+                                "type": "serde_json::value::Value",
+                                "kind": "json",
+                                "children": [{
+                                    "label": "::String",
+                                    "type": "alloc::string::String",
+                                    "kind": "leaf",
+                                    "value": "x",
+                                }],
+                            },
+                        ],
+                    }],
+                }],
+            }],
+        })
+    );
+}
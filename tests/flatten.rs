@@ -0,0 +1,176 @@
+use spectacle::{
+    flatten::{flatten, flatten_typed},
+    Spectacle,
+};
+use std::collections::BTreeMap;
+
+#[derive(Spectacle)]
+struct Config {
+    server: Server,
+    limits: BTreeMap<String, u32>,
+    bounds: (u8, Option<u8>),
+    tag: Option<char>,
+}
+
+#[derive(Spectacle)]
+struct Server {
+    name: String,
+    ports: Vec<u16>,
+    mode: Mode,
+    ratio: f32,
+}
+
+#[derive(Spectacle)]
+#[allow(dead_code)]
+enum Mode {
+    Off,
+    Fixed(i32),
+}
+
+fn config() -> Config {
+    let mut limits = BTreeMap::new();
+    limits.insert("cpu".to_string(), 2);
+    limits.insert("gpu \"x\"".to_string(), 1);
+    Config {
+        server: Server {
+            name: "svc\n".to_string(),
+            ports: vec![8080, 8443],
+            mode: Mode::Fixed(-1),
+            ratio: 0.1,
+        },
+        limits,
+        bounds: (1, None),
+        tag: Some('t'),
+    }
+}
+
+fn lines(pairs: &[(String, String)]) -> Vec<String> {
+    pairs
+        .iter()
+        .map(|(path, value)| format!("{} = {}", path, value))
+        .collect()
+}
+
+#[test]
+fn flatten_derived() {
+    assert_eq!(
+        lines(&flatten(&config())),
+        vec![
+            r#"server.name = "svc\n""#,
+            "server.ports[0] = 8080",
+            "server.ports[1] = 8443",
+            "server.mode.Fixed[0] = -1",
+            "server.ratio = 0.1",
+            r#"limits["cpu"] = 2"#,
+            r#"limits["gpu \"x\""] = 1"#,
+            "bounds[0] = 1",
+            r#"tag = "t""#,
+        ]
+    );
+}
+
+#[test]
+fn flatten_leaf() {
+    assert_eq!(lines(&flatten(&5_u8)), vec![" = 5"]);
+    assert!(flatten(&Vec::<u8>::new()).is_empty());
+    assert_eq!(lines(&flatten_typed(&Vec::<u8>::new())), vec![" = []"]);
+}
+
+#[test]
+fn flatten_empty_values() {
+    let mut config = config();
+    config.server.ports.clear();
+    config.server.mode = Mode::Off;
+    config.limits.clear();
+    let flattened = vec![
+        r#"server.name = "svc\n""#,
+        "server.ports = []",
+        r#"server.mode = "Off""#,
+        "server.ratio = 0.1",
+        "limits = {}",
+        "bounds[0] = 1",
+        r#"tag = "t""#,
+    ];
+    assert_eq!(lines(&flatten_typed(&config)), flattened);
+
+    // without `TypeInfo`, values with nothing beneath them are not known
+    assert_eq!(
+        lines(&flatten(&config)),
+        vec![
+            r#"server.name = "svc\n""#,
+            "server.ratio = 0.1",
+            "bounds[0] = 1",
+            r#"tag = "t""#,
+        ]
+    );
+}
+
+#[cfg(feature = "serde-json")]
+#[test]
+fn roundtrip_json() {
+    use serde_json::json;
+    use spectacle::flatten::unflatten;
+
+    let value = json!({
+        "name": "svc",
+        "ports": [80, {"tls": true}],
+        "weird key": null,
+        "nested": {"ratio": 1.5, "count": -3},
+        "e": [],
+        "f": {}
+    });
+    let pairs = flatten(&value);
+    assert_eq!(
+        lines(&pairs),
+        vec![
+            "e = []",
+            "f = {}",
+            r#"name = "svc""#,
+            "nested.count = -3",
+            "nested.ratio = 1.5",
+            "ports[0] = 80",
+            "ports[1].tls = true",
+            r#"["weird key"] = null"#,
+        ]
+    );
+    assert_eq!(unflatten(pairs).unwrap(), value);
+
+    // derived values are rebuilt as `serde_json` would serialize them
+    let mut config = config();
+    config.server.mode = Mode::Off;
+    config.server.ports.clear();
+    assert_eq!(
+        unflatten(flatten_typed(&config)).unwrap(),
+        json!({
+            "server": {
+                "name": "svc\n",
+                "ports": [],
+                "mode": "Off",
+                "ratio": 0.1
+            },
+            "limits": {"cpu": 2, "gpu \"x\"": 1},
+            "bounds": [1],
+            "tag": "t"
+        })
+    );
+}
+
+#[cfg(feature = "serde-json")]
+#[test]
+fn unflatten_errors() {
+    use spectacle::flatten::unflatten;
+
+    let err = unflatten(vec![("a[0]", "1"), ("a.b", "2")]).unwrap_err();
+    assert_eq!(err.to_string(), "a.b: expected an object, found an array");
+
+    let err = unflatten(vec![("a", "1"), ("a", "2")]).unwrap_err();
+    assert_eq!(err.to_string(), "a: a value is already present");
+
+    let err = unflatten(vec![("a[0", "1")]).unwrap_err();
+    assert_eq!(err.to_string(), "a[0: unterminated [");
+
+    assert_eq!(
+        unflatten(vec![("a[2]", "NaN")]).unwrap(),
+        serde_json::json!({"a": [null, null, "NaN"]})
+    );
+}
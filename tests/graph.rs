@@ -0,0 +1,94 @@
+use spectacle::{
+    graph::{to_dot, to_dot_typed, to_mermaid, to_mermaid_typed, GraphOptions},
+    Spectacle,
+};
+
+#[derive(Spectacle)]
+struct Service {
+    name: String,
+    ports: Vec<u16>,
+}
+
+fn service() -> Service {
+    Service {
+        name: "api \"v2\"".to_string(),
+        ports: vec![80, 443, 8080],
+    }
+}
+
+fn collapsed() -> GraphOptions {
+    GraphOptions {
+        max_items: Some(1),
+        ..GraphOptions::default()
+    }
+}
+
+#[test]
+fn dot() {
+    assert_eq!(
+        to_dot_typed(&service(), &collapsed()),
+        r#"digraph {
+    node [shape=box];
+    n0 [label="Service"];
+    n1 [label="String = \"api \\\"v2\\\"\""];
+    n2 [label="Vec<u16>"];
+    n3 [label="u16 = 80"];
+    n4 [label="… 2 more", style=dashed];
+    n0 -> n1 [label=".name"];
+    n0 -> n2 [label=".ports"];
+    n2 -> n3 [label="[0]"];
+    n2 -> n4;
+}
+"#
+    );
+}
+
+#[test]
+fn mermaid() {
+    assert_eq!(
+        to_mermaid_typed(&service(), &collapsed()),
+        r#"flowchart TD
+    n0["Service"]
+    n1["String = #quot;api \#quot;v2\#quot;#quot;"]
+    n2["Vec#lt;u16#gt;"]
+    n3["u16 = 80"]
+    n4["… 2 more"]
+    style n4 stroke-dasharray: 5 5
+    n0 -->|".name"| n1
+    n0 -->|".ports"| n2
+    n2 -->|"[0]"| n3
+    n2 -.-> n4
+"#
+    );
+}
+
+#[test]
+fn uncollapsed() {
+    let dot = to_dot_typed(&service(), &GraphOptions::default());
+    assert!(dot.contains("n5 [label=\"u16 = 8080\"];"));
+    assert!(!dot.contains("more"));
+}
+
+#[test]
+fn untyped() {
+    // without `TypeInfo`, only the types of primitives and strings are shown
+    assert_eq!(
+        to_dot(&service(), &collapsed()),
+        r#"digraph {
+    node [shape=box];
+    n0 [label="?"];
+    n1 [label="String = \"api \\\"v2\\\"\""];
+    n2 [label="?"];
+    n3 [label="u16 = 80"];
+    n4 [label="u16 = 443"];
+    n5 [label="u16 = 8080"];
+    n0 -> n1 [label=".name"];
+    n0 -> n2 [label=".ports"];
+    n2 -> n3 [label="[0]"];
+    n2 -> n4 [label="[1]"];
+    n2 -> n5 [label="[2]"];
+}
+"#
+    );
+    assert!(to_mermaid(&7u8, &collapsed()).contains("n0[\"u8 = 7\"]"));
+}
@@ -0,0 +1,190 @@
+#![cfg(feature = "serde-json")]
+
+use serde_json::json;
+use spectacle::json::{
+    infer::{infer, Kind, Schema},
+    JsonPath, Segment,
+};
+
+fn path(s: &str) -> JsonPath {
+    JsonPath(
+        s.split('.')
+            .filter(|s| !s.is_empty())
+            .map(|s| match s {
+                "*" => Segment::AnyIndex,
+                key => Segment::Key(key.to_string()),
+            })
+            .collect(),
+    )
+}
+
+#[test]
+fn kinds_presence_and_examples() {
+    let documents = vec![
+        json!({"id": 1, "name": "a", "tags": ["x", "y"], "extra": null}),
+        json!({"id": 2, "name": "b", "tags": [], "score": 0.5}),
+        json!({"id": 3, "name": null, "tags": ["x", 7]}),
+    ];
+    let schema = infer(&documents);
+
+    assert_eq!(schema.documents(), 3);
+    let root = schema.root();
+    assert!(root.is_only(Kind::Object));
+    assert!(root.is_required("id"));
+    assert!(root.is_required("name"));
+    assert!(!root.is_required("score"));
+    assert!(!root.is_required("missing"));
+
+    let id = schema.get(&path("id")).unwrap();
+    assert!(id.is_only(Kind::Integer));
+    assert_eq!(id.examples, vec![json!(1), json!(2), json!(3)]);
+
+    let name = schema.get(&path("name")).unwrap();
+    assert_eq!(name.kind_count(Kind::String), 2);
+    assert_eq!(name.kind_count(Kind::Null), 1);
+
+    let tags = schema.get(&path("tags.*")).unwrap();
+    assert_eq!(tags.count, 4);
+    assert_eq!(tags.kind_count(Kind::String), 3);
+    assert_eq!(tags.kind_count(Kind::Integer), 1);
+    assert_eq!(tags.examples, vec![json!("x"), json!("y"), json!(7)]);
+
+    let score = schema.get(&path("score")).unwrap();
+    assert_eq!(score.count, 1);
+    assert!(score.is_only(Kind::Float));
+}
+
+#[test]
+fn merge_is_equivalent_to_observing() {
+    let a = json!({"a": [{"b": true}]});
+    let b = json!({"a": [{"c": "d"}, {"b": false}]});
+
+    let mut merged = infer(Some(&a));
+    merged.merge(&infer(Some(&b)));
+    assert_eq!(merged, infer(vec![&a, &b]));
+}
+
+#[test]
+fn streaming_is_equivalent_to_observing() {
+    let documents = vec![
+        json!({"a": [{"b": true}, 1.5], "c": null}),
+        json!({"a": [], "d": "2021-03-04"}),
+        json!([1, "x"]),
+    ];
+    let lines: String = documents.iter().map(|d| format!("{}\n", d)).collect();
+
+    let mut streamed = Schema::new();
+    assert_eq!(streamed.observe_reader(lines.as_bytes()).unwrap(), 3);
+    assert_eq!(streamed, infer(&documents));
+}
+
+#[test]
+fn paths() {
+    let mut schema = Schema::with_max_examples(0);
+    schema.observe(&json!({"a": [1], "weird key": {"b": null}}));
+    let paths: Vec<_> = schema
+        .paths()
+        .into_iter()
+        .map(|(path, shape)| (path.to_string(), shape.examples.len()))
+        .collect();
+    assert_eq!(
+        paths,
+        vec![
+            ("$".to_string(), 0),
+            ("$.a".to_string(), 0),
+            ("$.a[*]".to_string(), 0),
+            ("$[\"weird key\"]".to_string(), 0),
+            ("$[\"weird key\"].b".to_string(), 0),
+        ]
+    );
+}
+
+#[test]
+fn string_formats() {
+    use spectacle::json::format::{self, Format};
+
+    let documents = vec![
+        json!({"at": "2021-03-04T05:06:07.89+01:00", "id": "67e55044-10b1-426f-9247-bb680e5fe0c8", "n": "-12"}),
+        json!({"at": "2021-03-04", "id": "not a uuid", "n": "1.5e3"}),
+        json!({"at": "2021-02-30", "id": "someone@example.com", "n": "https://example.com/"}),
+    ];
+    let schema = infer(&documents);
+
+    let at = schema.get(&path("at")).unwrap();
+    assert_eq!(
+        at.formats
+            .iter()
+            .map(|(f, n)| (f.name, *n))
+            .collect::<Vec<_>>(),
+        vec![("date", 1), ("date-time", 1)]
+    );
+    assert_eq!(at.format(), None);
+    assert_eq!(at.to_string(), "3 (string: 3 [date: 1, date-time: 1])");
+
+    let id = schema.get(&path("id")).unwrap();
+    assert_eq!(
+        id.formats.keys().copied().collect::<Vec<_>>(),
+        vec![format::EMAIL, format::UUID]
+    );
+
+    let n = schema.get(&path("n")).unwrap();
+    assert_eq!(
+        n.formats.keys().copied().collect::<Vec<_>>(),
+        vec![format::DECIMAL, format::INTEGER, format::URI]
+    );
+
+    // custom formats replace the defaults
+    const SHOUTING: Format = Format::new("shouting", None, |s| {
+        !s.is_empty() && s.chars().all(|c| c.is_ascii_uppercase())
+    });
+    let mut schema = Schema::new().formats(vec![SHOUTING]);
+    schema.observe(&json!(["HEY", "2021-03-04"]));
+    let items = schema.get(&path("*")).unwrap();
+    assert_eq!(items.formats.get(&SHOUTING), Some(&1));
+    assert_eq!(items.formats.len(), 1);
+}
+
+#[test]
+fn number_statistics() {
+    let documents = vec![
+        json!({"small": 1, "signed": -129, "big": 18446744073709551615u64, "mixed": 1, "float": 0.5}),
+        json!({"small": 200, "signed": 3, "big": 0, "mixed": 9007199254740993u64, "float": 0.1}),
+    ];
+    let schema = infer(&documents);
+    let numbers = |key| schema.get(&path(key)).unwrap().numbers;
+
+    let small = numbers("small");
+    assert_eq!(small.integers, 2);
+    assert_eq!(small.integer_range, Some((1, 200)));
+    assert!(!small.is_signed());
+    assert_eq!(small.narrowest_type(), Some("u8"));
+
+    let signed = numbers("signed");
+    assert!(signed.is_signed());
+    assert_eq!(signed.range, Some((-129.0, 3.0)));
+    assert_eq!(signed.narrowest_type(), Some("i16"));
+
+    let big = numbers("big");
+    assert_eq!(big.narrowest_type(), Some("u64"));
+    assert_eq!(big.inexact_in_f64, 1);
+    assert!(!big.loses_precision());
+
+    let mut mixed = numbers("mixed");
+    assert_eq!(mixed.narrowest_type(), Some("u64"));
+    mixed.merge(&numbers("float"));
+    assert_eq!(mixed.count(), 4);
+    assert_eq!(mixed.narrowest_type(), Some("f64"));
+    assert_eq!(mixed.narrowest_integer_type(), Some("u64"));
+    assert!(mixed.loses_precision());
+
+    let float = numbers("float");
+    assert_eq!(float.floats, 2);
+    assert_eq!(float.integer_range, None);
+    assert_eq!(float.narrowest_type(), Some("f64"));
+    let mut halves = Schema::new();
+    halves.observe(&json!([0.5, 0.25, 2]));
+    assert_eq!(
+        halves.get(&path("*")).unwrap().numbers.narrowest_type(),
+        Some("f32")
+    );
+}
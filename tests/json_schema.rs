@@ -0,0 +1,156 @@
+#![cfg(feature = "serde-json")]
+
+use serde_json::json;
+use spectacle::{
+    json::{infer::infer, json_schema},
+    Spectacle,
+};
+use std::collections::HashMap;
+
+#[test]
+fn inferred() {
+    let documents = vec![
+        json!({"id": 1, "at": "2021-03-04", "tags": ["a"], "score": 0.5}),
+        json!({"id": 2, "at": "2021-03-05", "tags": [], "score": 1}),
+        json!({"id": 3, "at": null, "tags": ["b"]}),
+    ];
+    let schema = json_schema::from_inferred(&infer(&documents));
+    assert_eq!(
+        schema,
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "at": {
+                    "type": ["null", "string"],
+                    "format": "date",
+                    "examples": ["2021-03-04", "2021-03-05", null],
+                },
+                "id": {"type": "integer", "examples": [1, 2, 3]},
+                "score": {"type": "number", "examples": [0.5, 1]},
+                "tags": {
+                    "type": "array",
+                    "items": {"type": "string", "examples": ["a", "b"]},
+                },
+            },
+            "required": ["at", "id", "tags"],
+        })
+    );
+}
+
+#[derive(Spectacle)]
+#[allow(dead_code)]
+struct Tree {
+    label: String,
+    weight: Option<u8>,
+    children: Vec<Tree>,
+    attributes: HashMap<String, Attribute>,
+}
+
+#[derive(Spectacle)]
+#[allow(dead_code)]
+enum Attribute {
+    Flag,
+    Other,
+    Count(i32),
+    Pair(bool, char),
+    Named { value: f64 },
+}
+
+#[test]
+fn derived() {
+    let schema = json_schema::from_type::<Tree>();
+    assert_eq!(
+        schema,
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "label": {"type": "string"},
+                "weight": {
+                    "anyOf": [
+                        {"type": "integer", "minimum": 0, "maximum": 255},
+                        {"type": "null"},
+                    ],
+                },
+                "children": {"type": "array", "items": {"$ref": "#"}},
+                "attributes": {
+                    "type": "object",
+                    "additionalProperties": {"$ref": "#/$defs/Attribute"},
+                },
+            },
+            "required": ["label", "children", "attributes"],
+            "$defs": {
+                "Attribute": {
+                    "oneOf": [
+                        {"type": "string", "enum": ["Flag", "Other"]},
+                        {
+                            "type": "object",
+                            "properties": {
+                                "Count": {
+                                    "type": "integer",
+                                    "minimum": -2147483648,
+                                    "maximum": 2147483647,
+                                },
+                            },
+                            "required": ["Count"],
+                            "additionalProperties": false,
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "Pair": {
+                                    "type": "array",
+                                    "prefixItems": [
+                                        {"type": "boolean"},
+                                        {"type": "string", "minLength": 1, "maxLength": 1},
+                                    ],
+                                    "items": false,
+                                    "minItems": 2,
+                                },
+                            },
+                            "required": ["Pair"],
+                            "additionalProperties": false,
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "Named": {
+                                    "type": "object",
+                                    "properties": {"value": {"type": "number"}},
+                                    "required": ["value"],
+                                },
+                            },
+                            "required": ["Named"],
+                            "additionalProperties": false,
+                        },
+                    ],
+                },
+            },
+        })
+    );
+}
+
+#[derive(Spectacle)]
+#[allow(dead_code)]
+struct Wrapper<T>(T);
+
+#[test]
+fn generic_definitions() {
+    let schema = json_schema::from_type::<Vec<(Wrapper<u8>, Wrapper<Tree>, [(); 2])>>();
+    let defs = schema["$defs"].as_object().unwrap();
+    assert_eq!(
+        defs.keys().collect::<Vec<_>>(),
+        vec!["Attribute", "Tree", "Wrapper_Tree", "Wrapper_u8"]
+    );
+    assert_eq!(defs["Wrapper_Tree"], json!({"$ref": "#/$defs/Tree"}));
+    assert_eq!(
+        schema["items"]["prefixItems"][2],
+        json!({
+            "type": "array",
+            "items": {"type": "null"},
+            "minItems": 2,
+            "maxItems": 2,
+        })
+    );
+}
@@ -0,0 +1,242 @@
+use spectacle::{
+    overrides::{apply, apply_typed, from_args, from_env_vars, Override, OverrideErrorKind},
+    Navigate, Spectacle,
+};
+use std::collections::BTreeMap;
+
+#[derive(Spectacle, Navigate, PartialEq, Debug)]
+struct Config {
+    database: Database,
+    mode: Mode,
+    debug: bool,
+    hosts: Vec<String>,
+    limits: BTreeMap<String, u32>,
+}
+
+#[derive(Spectacle, Navigate, PartialEq, Debug)]
+struct Database {
+    url: String,
+    pool: Pool,
+}
+
+#[derive(Spectacle, Navigate, PartialEq, Debug)]
+struct Pool {
+    max: u32,
+    timeout: Option<f64>,
+}
+
+#[derive(Spectacle, Navigate, PartialEq, Debug)]
+#[allow(dead_code)]
+enum Mode {
+    Off,
+    Fixed(i32),
+    Scaled { factor: f64 },
+}
+
+fn config() -> Config {
+    let mut limits = BTreeMap::new();
+    limits.insert("cpu".to_string(), 2);
+    Config {
+        database: Database {
+            url: "postgres://localhost".to_string(),
+            pool: Pool {
+                max: 4,
+                timeout: None,
+            },
+        },
+        mode: Mode::Fixed(1),
+        debug: false,
+        hosts: vec!["a".to_string(), "b".to_string()],
+        limits,
+    }
+}
+
+#[test]
+fn parse() {
+    let item: Override = "database.pool.max=10".parse().unwrap();
+    assert_eq!(item.key, vec!["database", "pool", "max"]);
+    assert_eq!(item.value, "10");
+
+    let err = "database.pool.max".parse::<Override>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "database.pool.max: expected an override of the form key=value"
+    );
+    assert!("database..max=1".parse::<Override>().is_err());
+}
+
+#[test]
+fn from_environment_and_arguments() {
+    let vars = vec![
+        ("APP__DATABASE__POOL__MAX".to_string(), "10".to_string()),
+        ("APP__DEBUG".to_string(), "TRUE".to_string()),
+        ("OTHER__DEBUG".to_string(), "false".to_string()),
+        ("PATH".to_string(), "/bin".to_string()),
+    ];
+    let overrides = from_env_vars("APP", vars);
+    assert_eq!(
+        overrides
+            .iter()
+            .map(Override::dotted_key)
+            .collect::<Vec<_>>(),
+        vec!["database.pool.max", "debug"]
+    );
+
+    let mut config = config();
+    apply_typed(&mut config, &overrides).unwrap();
+    assert_eq!(config.database.pool.max, 10);
+    assert!(config.debug);
+
+    let args = vec![
+        "serve",
+        "--set",
+        "database.url=postgres://db",
+        "--set=mode=Off",
+        "--verbose",
+    ];
+    let overrides = from_args(args).unwrap();
+    apply_typed(&mut config, &overrides).unwrap();
+    assert_eq!(config.database.url, "postgres://db");
+    assert_eq!(config.mode, Mode::Off);
+
+    assert!(from_args(vec!["--set"]).is_err());
+}
+
+#[test]
+fn leaf_types() {
+    let mut config = config();
+    let overrides: Vec<Override> = vec![
+        "database.pool.timeout=2.5",
+        "hosts.1=c",
+        "limits.cpu=8",
+        "mode.scaled.factor=3",
+    ]
+    .into_iter()
+    .map(|s| s.parse().unwrap())
+    .collect();
+
+    // the mode is not scaled, so its factor cannot be set
+    let err = apply_typed(&mut config, &overrides).unwrap_err();
+    assert_eq!(err.key, "mode.scaled.factor");
+    assert!(matches!(err.kind, OverrideErrorKind::Path(_)));
+    assert_eq!(config.database.pool.timeout, Some(2.5));
+    assert_eq!(config.hosts, vec!["a", "c"]);
+    assert_eq!(config.limits["cpu"], 8);
+
+    let overrides = vec!["database.pool.timeout=".parse().unwrap()];
+    apply_typed(&mut config, &overrides).unwrap();
+    assert_eq!(config.database.pool.timeout, None);
+
+    let overrides = vec!["mode.fixed.0=-3".parse().unwrap()];
+    config.mode = Mode::Fixed(1);
+    apply_typed(&mut config, &overrides).unwrap();
+    assert_eq!(config.mode, Mode::Fixed(-3));
+}
+
+#[test]
+fn errors() {
+    let mut config = config();
+    let mut error = |s: &str| {
+        let overrides = vec![s.parse().unwrap()];
+        apply_typed(&mut config, &overrides)
+            .unwrap_err()
+            .to_string()
+    };
+
+    assert_eq!(
+        error("database.pool.max=ten"),
+        "database.pool.max: \"ten\" is not a valid u32"
+    );
+    assert_eq!(
+        error("database.size=1"),
+        "database.size: size does not lead to a value"
+    );
+    assert_eq!(
+        error("mode=Sideways"),
+        "mode: \"Sideways\" is not a valid overrides::Mode"
+    );
+    assert_eq!(
+        error("database.pool=1"),
+        "database.pool: a value of type overrides::Pool cannot be parsed from text"
+    );
+    assert_eq!(error("hosts.5=x"), "hosts.5: $.hosts: no child at [5]");
+}
+
+#[cfg(feature = "serde-json")]
+#[test]
+fn json_values() {
+    #[derive(Spectacle, Navigate)]
+    struct Settings {
+        extra: serde_json::Value,
+    }
+
+    let mut settings = Settings {
+        extra: serde_json::json!({"retries": 1}),
+    };
+    let overrides: Vec<Override> = vec!["extra.retries=[1, 2]", "extra.retries.x=1"]
+        .into_iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+    apply_typed(&mut settings, &overrides[..1]).unwrap();
+    assert_eq!(settings.extra, serde_json::json!({"retries": [1, 2]}));
+    assert!(apply_typed(&mut settings, &overrides[1..]).is_err());
+
+    apply_typed(&mut settings, &["extra=plain".parse().unwrap()]).unwrap();
+    assert_eq!(settings.extra, serde_json::json!("plain"));
+
+    // JSON objects are passed through without their values being known
+    settings.extra = serde_json::json!({"retries": 1});
+    apply(&mut settings, &overrides[..1]).unwrap();
+    assert_eq!(settings.extra, serde_json::json!({"retries": [1, 2]}));
+    assert!(apply(&mut settings, &overrides[1..]).is_err());
+}
+
+#[test]
+fn untyped() {
+    // keys are resolved against the values present
+    let mut config = config();
+    config.database.pool.timeout = Some(1.0);
+    let overrides: Vec<Override> = vec![
+        "database.pool.max=10",
+        "database.pool.timeout=2.5",
+        "hosts.1=c",
+        "limits.cpu=8",
+        "mode.fixed.0=-3",
+    ]
+    .into_iter()
+    .map(|s| s.parse().unwrap())
+    .collect();
+    apply(&mut config, &overrides).unwrap();
+    assert_eq!(config.database.pool.max, 10);
+    assert_eq!(config.database.pool.timeout, Some(2.5));
+    assert_eq!(config.hosts, vec!["a", "c"]);
+    assert_eq!(config.limits["cpu"], 8);
+    assert_eq!(config.mode, Mode::Fixed(-3));
+
+    let overrides: Vec<Override> = vec!["database.pool.timeout=", "mode=Off"]
+        .into_iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+    apply(&mut config, &overrides).unwrap();
+    assert_eq!(config.database.pool.timeout, None);
+    assert_eq!(config.mode, Mode::Off);
+
+    let mut error = |s: &str| {
+        let overrides = vec![s.parse().unwrap()];
+        apply(&mut config, &overrides).unwrap_err().to_string()
+    };
+    // a `None` has no contents to parse
+    assert_eq!(
+        error("database.pool.timeout=2.5"),
+        "database.pool.timeout: \"2.5\" is not a valid core::option::Option<f64>"
+    );
+    assert_eq!(
+        error("mode=Sideways"),
+        "mode: \"Sideways\" is not a valid overrides::Mode"
+    );
+    assert_eq!(error("hosts.5=x"), "hosts.5: 5 does not lead to a value");
+    assert_eq!(
+        error("database.pool.max=ten"),
+        "database.pool.max: \"ten\" is not a valid u32"
+    );
+}
@@ -0,0 +1,154 @@
+#![cfg(feature = "serde-json")]
+
+use serde_json::json;
+use spectacle::{
+    json::patch::{apply, diff, diff_serialize, Operation, Patch},
+    Breadcrumb,
+};
+
+#[test]
+fn diff_and_apply() {
+    let old = json!({
+        "name": "svc",
+        "ports": [80, 443, 8080],
+        "limits": {"cpu": 2, "a/b": 1},
+        "tags": ["x"],
+    });
+    let new = json!({
+        "name": "api",
+        "ports": [80],
+        "limits": {"cpu": 2, "memory": 512},
+        "tags": ["x", "y", "z"],
+    });
+    let patch = diff(&old, &new);
+    assert_eq!(
+        patch.to_json(),
+        json!([
+            {"op": "remove", "path": "/limits/a~1b"},
+            {"op": "add", "path": "/limits/memory", "value": 512},
+            {"op": "replace", "path": "/name", "value": "api"},
+            {"op": "remove", "path": "/ports/2"},
+            {"op": "remove", "path": "/ports/1"},
+            {"op": "add", "path": "/tags/1", "value": "y"},
+            {"op": "add", "path": "/tags/2", "value": "z"},
+        ])
+    );
+
+    let mut patched = old.clone();
+    apply(&mut patched, &patch).unwrap();
+    assert_eq!(patched, new);
+    assert!(diff(&new, &patched).is_empty());
+}
+
+#[test]
+fn every_operation() {
+    let mut value = json!({"a": {"b": [1, 2]}, "c": "d"});
+    let patch = Patch::from_json(&json!([
+        {"op": "test", "path": "/a/b/1", "value": 2.0},
+        {"op": "add", "path": "/a/b/-", "value": 3},
+        {"op": "copy", "from": "/a/b", "path": "/e"},
+        {"op": "move", "from": "/c", "path": "/a/c"},
+        {"op": "replace", "path": "/a/b/0", "value": null},
+        {"op": "add", "path": "", "value": {"root": [value.clone()]}},
+        {"op": "remove", "path": "/root/0/c"},
+    ]))
+    .unwrap();
+    apply(&mut value, &patch).unwrap();
+    assert_eq!(value, json!({"root": [{"a": {"b": [1, 2]}}]}));
+    assert_eq!(Patch::from_json(&patch.to_json()).unwrap(), patch);
+}
+
+#[test]
+fn errors_are_located() {
+    let original = json!({"a": {"b": [1, 2]}});
+    let mut value = original.clone();
+    let patch = Patch(vec![
+        Operation::Remove {
+            path: "/a/b/0".to_string(),
+        },
+        Operation::Add {
+            path: "/a/b/5".to_string(),
+            value: json!(0),
+        },
+    ]);
+    let error = apply(&mut value, &patch).unwrap_err();
+    assert_eq!(error.operation, 1);
+    assert_eq!(error.path.to_string(), "$.a.b");
+    assert_eq!(
+        error.breadcrumbs,
+        vec![
+            Breadcrumb::Variant("Object"),
+            Breadcrumb::Index("a".to_string()),
+            Breadcrumb::Variant("Object"),
+            Breadcrumb::Index("b".to_string()),
+        ]
+        .into_iter()
+        .collect()
+    );
+    assert_eq!(
+        error.to_string(),
+        "operation 1 at $.a.b: index 5 is out of bounds"
+    );
+    // the patch is applied atomically
+    assert_eq!(value, original);
+
+    let failures = [
+        (
+            json!({"op": "remove", "path": "/a/x"}),
+            "$.a: no member \"x\"",
+        ),
+        (
+            json!({"op": "replace", "path": "/a/b/01", "value": 0}),
+            "$.a.b: \"01\" is not an array index",
+        ),
+        (
+            json!({"op": "test", "path": "/a/b/0", "value": 2}),
+            "$.a.b[0]: test failed: expected 2, found 1",
+        ),
+        (
+            json!({"op": "move", "from": "/a", "path": "/a/c"}),
+            "$.a: cannot move a value into itself",
+        ),
+        (
+            json!({"op": "add", "path": "/a/b/0/c", "value": 0}),
+            "$.a.b[0]: cannot add to integer",
+        ),
+        (
+            json!({"op": "remove", "path": "a"}),
+            "$: invalid JSON Pointer \"a\"",
+        ),
+    ];
+    for (operation, expected) in &failures {
+        let patch = Patch(vec![Operation::from_json(operation).unwrap()]);
+        let error = apply(&mut value, &patch).unwrap_err();
+        assert_eq!(format!("{}: {}", error.path, error.message), *expected);
+    }
+
+    assert!(Patch::from_json(&json!([{"op": "frob", "path": ""}])).is_err());
+    assert!(Patch::from_json(&json!([{"op": "add", "path": "/a"}])).is_err());
+}
+
+#[test]
+fn typed_values() {
+    #[derive(serde::Serialize)]
+    struct Config {
+        name: String,
+        replicas: Option<u8>,
+    }
+
+    let patch = diff_serialize(
+        &Config {
+            name: "svc".to_string(),
+            replicas: None,
+        },
+        &Config {
+            name: "svc".to_string(),
+            replicas: Some(3),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        patch.to_json(),
+        json!([{"op": "replace", "path": "/replicas", "value": 3}])
+    );
+}
@@ -0,0 +1,379 @@
+use spectacle::{
+    path::{get, insert, remove, set, set_variant, Navigate, PathErrorKind},
+    Breadcrumb, Breadcrumbs, Introspect, Options, Spectacle,
+};
+use std::collections::{BTreeMap, HashSet};
+
+#[derive(Spectacle, Navigate)]
+struct Config {
+    name: String,
+    ports: Vec<u16>,
+    mode: Mode,
+    limits: BTreeMap<String, u32>,
+    tags: HashSet<String>,
+    bounds: (u8, Option<u8>),
+}
+
+#[derive(Spectacle, Navigate, PartialEq, Debug)]
+#[allow(dead_code)]
+enum Mode {
+    Off,
+    Fixed(i32, i32),
+    Scaled { factor: f64 },
+}
+
+fn config() -> Config {
+    let mut limits = BTreeMap::new();
+    limits.insert("cpu".to_string(), 2);
+    Config {
+        name: "svc".to_string(),
+        ports: vec![80, 443],
+        mode: Mode::Fixed(1, 2),
+        limits,
+        tags: vec!["a".to_string()].into_iter().collect(),
+        bounds: (1, Some(9)),
+    }
+}
+
+fn path(breadcrumbs: Vec<Breadcrumb>) -> Breadcrumbs {
+    breadcrumbs.into_iter().collect()
+}
+
+#[test]
+fn every_visited_path_can_be_read() {
+    let config = config();
+    let options = Options {
+        map_keys: true,
+        ..Options::default()
+    };
+    let mut visited = 0;
+    config.introspect_with(&options, |breadcrumbs, value| {
+        let found = get(&config, breadcrumbs).expect("visited paths can be found");
+        assert_eq!(
+            found as *const dyn std::any::Any as *const (),
+            value as *const dyn std::any::Any as *const ()
+        );
+        visited += 1;
+    });
+    assert_eq!(visited, 17);
+}
+
+#[test]
+fn set_replaces_values() {
+    let mut config = config();
+
+    set(
+        &mut config,
+        &path(vec![Breadcrumb::Field("name")]),
+        Box::new("api".to_string()),
+    )
+    .unwrap();
+    assert_eq!(config.name, "api");
+
+    let port = path(vec![
+        Breadcrumb::Field("ports"),
+        Breadcrumb::Index("1".into()),
+    ]);
+    set(&mut config, &port, Box::new(8443_u16)).unwrap();
+    assert_eq!(config.ports, vec![80, 8443]);
+
+    let limit = path(vec![
+        Breadcrumb::Field("limits"),
+        Breadcrumb::Index("\"cpu\"".into()),
+    ]);
+    set(&mut config, &limit, Box::new(4_u32)).unwrap();
+    assert_eq!(config.limits["cpu"], 4);
+
+    let fixed = path(vec![
+        Breadcrumb::Field("mode"),
+        Breadcrumb::Variant("Fixed"),
+        Breadcrumb::TupleIndex(1),
+    ]);
+    set(&mut config, &fixed, Box::new(5_i32)).unwrap();
+    assert_eq!(config.mode, Mode::Fixed(1, 5));
+
+    let upper = path(vec![
+        Breadcrumb::Field("bounds"),
+        Breadcrumb::TupleIndex(1),
+        Breadcrumb::Variant("Some"),
+    ]);
+    set(&mut config, &upper, Box::new(10_u8)).unwrap();
+    assert_eq!(config.bounds, (1, Some(10)));
+
+    set(
+        &mut config,
+        &path(vec![Breadcrumb::Field("mode")]),
+        Box::new(Mode::Off),
+    )
+    .unwrap();
+    assert_eq!(config.mode, Mode::Off);
+}
+
+#[test]
+fn errors() {
+    let mut config = config();
+
+    let err = set(
+        &mut config,
+        &path(vec![Breadcrumb::Field("name")]),
+        Box::new(1_u8),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.kind,
+        PathErrorKind::TypeMismatch {
+            expected: "alloc::string::String"
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "$.name: expected a value of type alloc::string::String"
+    );
+
+    let scaled = path(vec![
+        Breadcrumb::Field("mode"),
+        Breadcrumb::Variant("Scaled"),
+        Breadcrumb::Field("factor"),
+    ]);
+    let err = set(&mut config, &scaled, Box::new(2.0_f64)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "$.mode: variant Scaled is not active; the active variant is Fixed"
+    );
+
+    let missing = path(vec![
+        Breadcrumb::Field("mode"),
+        Breadcrumb::Variant("Fixed"),
+        Breadcrumb::TupleIndex(2),
+    ]);
+    let err = get(&config, &missing).unwrap_err();
+    assert_eq!(err.kind, PathErrorKind::NotFound(Breadcrumb::TupleIndex(2)));
+    assert_eq!(config.child(&[]).err(), Some(PathErrorKind::EmptyPath));
+
+    let port = path(vec![
+        Breadcrumb::Field("ports"),
+        Breadcrumb::Index("2".into()),
+    ]);
+    let err = get(&config, &port).unwrap_err();
+    assert_eq!(err.to_string(), "$.ports: no child at [2]");
+
+    let tag = path(vec![
+        Breadcrumb::Field("tags"),
+        // the stable hash of "a"
+        Breadcrumb::SetMember("089bc907b544c769".to_string()),
+    ]);
+    assert_eq!(
+        get(&config, &tag)
+            .unwrap()
+            .downcast_ref::<String>()
+            .unwrap(),
+        "a"
+    );
+    let err = set(&mut config, &tag, Box::new("b".to_string())).unwrap_err();
+    assert!(matches!(
+        err.kind,
+        PathErrorKind::Immutable(Breadcrumb::SetMember(_))
+    ));
+
+    let key = path(vec![
+        Breadcrumb::Field("limits"),
+        Breadcrumb::MapKey("\"cpu\"".into()),
+    ]);
+    assert!(get(&config, &key).is_ok());
+    let err = set(&mut config, &key, Box::new("gpu".to_string())).unwrap_err();
+    assert!(matches!(
+        err.kind,
+        PathErrorKind::Immutable(Breadcrumb::MapKey(_))
+    ));
+}
+
+#[test]
+fn insert_and_remove() {
+    let mut config = config();
+
+    insert(
+        &mut config,
+        &path(vec![Breadcrumb::Field("ports")]),
+        Box::new(8080_u16),
+    )
+    .unwrap();
+    assert_eq!(config.ports, vec![80, 443, 8080]);
+
+    let port = path(vec![
+        Breadcrumb::Field("ports"),
+        Breadcrumb::Index("0".into()),
+    ]);
+    let removed = remove(&mut config, &port).unwrap();
+    assert_eq!(*removed.downcast::<u16>().unwrap(), 80);
+    assert_eq!(config.ports, vec![443, 8080]);
+
+    let limits = path(vec![Breadcrumb::Field("limits")]);
+    insert(
+        &mut config,
+        &limits,
+        Box::new(("memory".to_string(), 512_u32)),
+    )
+    .unwrap();
+    assert_eq!(config.limits["memory"], 512);
+
+    let cpu = path(vec![
+        Breadcrumb::Field("limits"),
+        Breadcrumb::Index("\"cpu\"".into()),
+    ]);
+    let removed = remove(&mut config, &cpu).unwrap();
+    assert_eq!(*removed.downcast::<u32>().unwrap(), 2);
+    assert_eq!(config.limits.keys().collect::<Vec<_>>(), vec!["memory"]);
+
+    let memory = path(vec![
+        Breadcrumb::Field("limits"),
+        Breadcrumb::MapKey("\"memory\"".into()),
+    ]);
+    let removed = remove(&mut config, &memory).unwrap();
+    assert_eq!(*removed.downcast::<String>().unwrap(), "memory");
+    assert!(config.limits.is_empty());
+
+    let tags = path(vec![Breadcrumb::Field("tags")]);
+    insert(&mut config, &tags, Box::new("b".to_string())).unwrap();
+    assert_eq!(config.tags.len(), 2);
+}
+
+#[test]
+fn remove_from_collections() {
+    use std::collections::{BTreeSet, BinaryHeap, LinkedList, VecDeque};
+
+    let second = path(vec![Breadcrumb::Index("1".into())]);
+    let mut deque: VecDeque<u8> = vec![1, 2, 3].into();
+    remove(&mut deque, &second).unwrap();
+    assert_eq!(deque, vec![1, 3]);
+    let mut list: LinkedList<u8> = vec![1, 2, 3].into_iter().collect();
+    remove(&mut list, &second).unwrap();
+    assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 3]);
+
+    let member = path(vec![Breadcrumb::SetMember("1".into())]);
+    let mut set: BTreeSet<u8> = vec![3, 1, 2].into_iter().collect();
+    let removed = remove(&mut set, &member).unwrap();
+    assert_eq!(*removed.downcast::<u8>().unwrap(), 2);
+    assert_eq!(set.into_iter().collect::<Vec<_>>(), vec![1, 3]);
+    let mut heap: BinaryHeap<u8> = vec![3, 1, 2].into_iter().collect();
+    remove(&mut heap, &member).unwrap();
+    assert_eq!(heap.len(), 2);
+    assert_eq!(heap.peek(), Some(&3));
+    let err = remove(&mut heap, &path(vec![Breadcrumb::SetMember("2".into())])).unwrap_err();
+    assert_eq!(
+        err.kind,
+        PathErrorKind::NotFound(Breadcrumb::SetMember("2".into()))
+    );
+}
+
+#[test]
+fn insert_and_remove_errors() {
+    let mut config = config();
+
+    let limits = path(vec![Breadcrumb::Field("limits")]);
+    let err = insert(&mut config, &limits, Box::new(512_u32)).unwrap_err();
+    assert_eq!(
+        err.kind,
+        PathErrorKind::TypeMismatch {
+            expected: "(alloc::string::String, u32)"
+        }
+    );
+
+    let name = path(vec![Breadcrumb::Field("name")]);
+    let err = insert(&mut config, &name, Box::new('x')).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "$.name: a value of type alloc::string::String is not a collection"
+    );
+
+    let port = path(vec![
+        Breadcrumb::Field("ports"),
+        Breadcrumb::Index("5".into()),
+    ]);
+    let err = remove(&mut config, &port).unwrap_err();
+    assert_eq!(err.to_string(), "$.ports: no child at [5]");
+
+    let err = remove(&mut config, &Breadcrumbs::new()).unwrap_err();
+    assert_eq!(err.kind, PathErrorKind::EmptyPath);
+}
+
+#[test]
+fn change_variants() {
+    let mut config = config();
+    let mode = path(vec![Breadcrumb::Field("mode")]);
+
+    set_variant(&mut config, &mode, "Scaled", Some(Box::new(1.5_f64))).unwrap();
+    assert_eq!(config.mode, Mode::Scaled { factor: 1.5 });
+    set_variant(&mut config, &mode, "Off", None).unwrap();
+    assert_eq!(config.mode, Mode::Off);
+
+    let err = set_variant(&mut config, &mode, "Fixed", Some(Box::new(1_i32))).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "$.mode: variant Fixed of path::Mode has 2 fields, so cannot be set from one value"
+    );
+    let err = set_variant(&mut config, &mode, "Sideways", None).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "$.mode: path::Mode has no variant Sideways with no fields"
+    );
+
+    let upper = path(vec![Breadcrumb::Field("bounds"), Breadcrumb::TupleIndex(1)]);
+    set_variant(&mut config, &upper, "None", None).unwrap();
+    assert_eq!(config.bounds, (1, None));
+    let err = set_variant(&mut config, &upper, "Some", Some(Box::new(1_u16))).unwrap_err();
+    assert_eq!(err.kind, PathErrorKind::TypeMismatch { expected: "u8" });
+}
+
+#[cfg(feature = "serde-json")]
+#[test]
+fn json_values() {
+    let mut value = serde_json::json!({"a": [1, {"b": true}]});
+    let b = path(vec![
+        Breadcrumb::Variant("Object"),
+        Breadcrumb::Index("a".into()),
+        Breadcrumb::Variant("Array"),
+        Breadcrumb::Index("1".into()),
+        Breadcrumb::Variant("Object"),
+        Breadcrumb::Index("b".into()),
+    ]);
+    set(&mut value, &b, Box::new(serde_json::json!("yes"))).unwrap();
+    assert_eq!(value, serde_json::json!({"a": [1, {"b": "yes"}]}));
+
+    let mut inner = b.clone();
+    inner.push_back(Breadcrumb::Variant("Bool"));
+    let err = get(&value, &inner).unwrap_err();
+    assert_eq!(err.breadcrumbs, b);
+    assert_eq!(
+        err.kind,
+        PathErrorKind::InactiveVariant {
+            requested: "Bool",
+            active: "String"
+        }
+    );
+
+    let a = path(vec![
+        Breadcrumb::Variant("Object"),
+        Breadcrumb::Index("a".into()),
+    ]);
+    insert(&mut value, &a, Box::new(serde_json::json!(null))).unwrap();
+    insert(
+        &mut value,
+        &Breadcrumbs::new(),
+        Box::new(("c".to_string(), serde_json::json!(3))),
+    )
+    .unwrap();
+    let mut first = a.clone();
+    first.push_back(Breadcrumb::Variant("Array"));
+    first.push_back(Breadcrumb::Index("0".into()));
+    remove(&mut value, &first).unwrap();
+    assert_eq!(
+        value,
+        serde_json::json!({"a": [{"b": "yes"}, null], "c": 3})
+    );
+
+    // the element of an object or array may be removed from the `Value`
+    let c = path(vec![Breadcrumb::Index("c".into())]);
+    let removed = remove(&mut value, &c).unwrap();
+    assert_eq!(*removed.downcast::<serde_json::Value>().unwrap(), 3);
+    assert_eq!(value, serde_json::json!({"a": [{"b": "yes"}, null]}));
+}
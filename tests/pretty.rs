@@ -0,0 +1,115 @@
+use spectacle::{
+    pretty::{pretty, pretty_typed, PrettyOptions},
+    Spectacle,
+};
+use std::collections::BTreeMap;
+
+#[derive(Spectacle)]
+struct Config {
+    name: String,
+    ports: Vec<u16>,
+    mode: Mode,
+    labels: BTreeMap<String, Option<char>>,
+}
+
+#[derive(Spectacle)]
+#[allow(dead_code)]
+enum Mode {
+    Off,
+    Fixed(i32),
+}
+
+fn config() -> Config {
+    let mut labels = BTreeMap::new();
+    labels.insert("a".to_string(), Some('x'));
+    labels.insert("b".to_string(), None);
+    Config {
+        name: "svc".to_string(),
+        ports: vec![80, 443, 8080],
+        mode: Mode::Fixed(-1),
+        labels,
+    }
+}
+
+#[test]
+fn tree() {
+    assert_eq!(
+        pretty_typed(&config(), &PrettyOptions::default()),
+        r#"$: Config
+├── .name: String = "svc"
+├── .ports: Vec<u16>
+│   ├── [0]: u16 = 80
+│   ├── [1]: u16 = 443
+│   └── [2]: u16 = 8080
+├── .mode: Mode
+│   └── ::Fixed.0: i32 = -1
+└── .labels: BTreeMap<String, Option<char>>
+    ├── ["a"]: Option<char>
+    │   └── ::Some: char = 'x'
+    └── ["b"]: Option<char>
+"#
+    );
+}
+
+#[test]
+fn limits() {
+    let options = PrettyOptions {
+        max_depth: Some(1),
+        max_items: Some(2),
+        ..PrettyOptions::default()
+    };
+    assert_eq!(
+        pretty_typed(&config(), &options),
+        r#"$: Config
+├── .name: String = "svc"
+├── .ports: Vec<u16>
+│   └── … 3 more
+├── .mode: Mode
+│   └── … 1 more
+└── .labels: BTreeMap<String, Option<char>>
+    └── … 2 more
+"#
+    );
+
+    let options = PrettyOptions {
+        max_items: Some(2),
+        ..PrettyOptions::default()
+    };
+    let rendered = pretty_typed(&config(), &options);
+    assert!(rendered.contains("│   ├── [1]: u16 = 443\n│   └── … 1 more\n"));
+    assert!(rendered.contains("::Fixed.0"));
+}
+
+#[test]
+fn color() {
+    let options = PrettyOptions {
+        color: true,
+        ..PrettyOptions::default()
+    };
+    let rendered = pretty(&7u8, &options);
+    assert_eq!(
+        rendered,
+        "\x1b[1;36m$\x1b[0m: \x1b[2mu8\x1b[0m = \x1b[33m7\x1b[0m\n"
+    );
+}
+
+#[test]
+fn untyped() {
+    // without `TypeInfo`, only the types of primitives and strings are shown
+    assert_eq!(
+        pretty(&config(), &PrettyOptions::default()),
+        r#"$
+├── .name: String = "svc"
+├── .ports
+│   ├── [0]: u16 = 80
+│   ├── [1]: u16 = 443
+│   └── [2]: u16 = 8080
+├── .mode
+│   └── ::Fixed.0: i32 = -1
+└── .labels
+    ├── ["a"]
+    │   └── ::Some: char = 'x'
+    └── ["b"]
+"#
+    );
+}
@@ -0,0 +1,94 @@
+#![cfg(feature = "serde-json")]
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use spectacle::{json::report::Report, Spectacle};
+use std::collections::HashMap;
+
+#[derive(Deserialize, Spectacle)]
+struct Order {
+    id: u64,
+    items: Vec<Item>,
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Deserialize, Spectacle)]
+struct Item {
+    sku: String,
+    details: Value,
+}
+
+#[test]
+fn leftovers_are_grouped_by_normalized_path() {
+    let orders: Vec<Order> = serde_json::from_value(json!([
+        {
+            "id": 1,
+            "items": [
+                {"sku": "a", "details": {"color": "red"}},
+                {"sku": "b", "details": null},
+            ],
+            "note": "fragile",
+        },
+        {
+            "id": 2,
+            "items": [{"sku": "c", "details": {"color": "blue", "size": 3}}],
+            "note": null,
+            "priority": 1,
+        },
+    ]))
+    .unwrap();
+
+    let mut report = Report::new();
+    for order in &orders {
+        report.observe(order);
+    }
+
+    let groups = report.groups();
+    assert_eq!(
+        groups.keys().collect::<Vec<_>>(),
+        vec![
+            r#"$.extra["note"]"#,
+            r#"$.extra["priority"]"#,
+            "$.items[*].details",
+        ]
+    );
+
+    let details = &groups["$.items[*].details"];
+    assert_eq!(details.documents(), 3);
+    assert!(details
+        .get(&spectacle::json::JsonPath(vec![
+            spectacle::json::Segment::Key("size".into())
+        ]))
+        .is_some());
+
+    assert_eq!(
+        report.to_string(),
+        r#"$.extra["note"]: 2 (null: 1, string: 1)
+$.extra["priority"]: 1 (integer: 1)
+$.items[*].details: 3 (null: 1, object: 2)
+"#
+    );
+}
+
+#[test]
+fn only_list_indices_are_collapsed() {
+    #[derive(Spectacle)]
+    struct Mixed {
+        by_id: HashMap<u32, Value>,
+        members: serde_json::Map<String, Value>,
+        list: Vec<Value>,
+    }
+
+    let mixed = Mixed {
+        by_id: vec![(1, json!(1)), (2, json!("two"))].into_iter().collect(),
+        members: serde_json::from_value(json!({"3": true})).unwrap(),
+        list: vec![json!(1), json!(2)],
+    };
+    let report = spectacle::json::report::report(&mixed);
+    assert_eq!(
+        report.groups().keys().collect::<Vec<_>>(),
+        vec!["$.by_id[1]", "$.by_id[2]", "$.list[*]", "$.members[3]"]
+    );
+    assert_eq!(report.groups()["$.list[*]"].documents(), 2);
+}
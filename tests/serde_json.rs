@@ -0,0 +1,209 @@
+#![cfg(feature = "serde-json")]
+
+use serde_json::{json, value::RawValue, Map, Number, Value};
+use spectacle::{Breadcrumb, Breadcrumbs, Introspect, Options};
+
+/// Describe the type of a visited item, for those types which can appear in a `Value`.
+fn type_of(visit: &dyn std::any::Any) -> &'static str {
+    if visit.is::<Value>() {
+        "Value"
+    } else if visit.is::<Map<String, Value>>() {
+        "Map"
+    } else if visit.is::<Vec<Value>>() {
+        "Vec"
+    } else if visit.is::<Number>() {
+        "Number"
+    } else if visit.is::<String>() {
+        "String"
+    } else if visit.is::<bool>() {
+        "bool"
+    } else if visit.is::<()>() {
+        "()"
+    } else if visit.is::<u64>() {
+        "u64"
+    } else if visit.is::<i64>() {
+        "i64"
+    } else if visit.is::<f64>() {
+        "f64"
+    } else if visit.is::<Box<RawValue>>() {
+        "RawValue"
+    } else {
+        "unknown"
+    }
+}
+
+fn visits<T: Introspect>(value: &T) -> Vec<(Breadcrumbs, &'static str)> {
+    visits_with(value, &Options::default())
+}
+
+fn visits_with<T: Introspect>(value: &T, options: &Options) -> Vec<(Breadcrumbs, &'static str)> {
+    let mut visits = Vec::new();
+    value.introspect_with(options, |breadcrumbs, visit| {
+        visits.push((breadcrumbs.clone(), type_of(visit)))
+    });
+    visits
+}
+
+fn crumbs(crumbs: &[Breadcrumb]) -> Breadcrumbs {
+    crumbs.iter().cloned().collect()
+}
+
+use Breadcrumb::{Index, Variant};
+
+#[test]
+fn null() {
+    assert_eq!(
+        visits(&Value::Null),
+        vec![(crumbs(&[]), "Value"), (crumbs(&[Variant("Null")]), "()"),]
+    );
+}
+
+#[test]
+fn bool() {
+    assert_eq!(
+        visits(&json!(true)),
+        vec![(crumbs(&[]), "Value"), (crumbs(&[Variant("Bool")]), "bool"),]
+    );
+}
+
+#[test]
+fn number() {
+    assert_eq!(
+        visits(&json!(12.5)),
+        vec![
+            (crumbs(&[]), "Value"),
+            (crumbs(&[Variant("Number")]), "Number"),
+            (crumbs(&[Variant("Number"), Variant("Float")]), "f64"),
+        ]
+    );
+}
+
+#[test]
+fn number_forms() {
+    for (number, variant, ty) in [
+        (json!(12), "PosInt", "u64"),
+        (json!(-12), "NegInt", "i64"),
+        (json!(-1.5), "Float", "f64"),
+    ] {
+        let number: Number = serde_json::from_value(number).unwrap();
+        assert_eq!(
+            visits(&number),
+            vec![(crumbs(&[]), "Number"), (crumbs(&[Variant(variant)]), ty)]
+        );
+    }
+
+    let mut floats: Vec<f64> = Vec::new();
+    Number::from_f64(0.25)
+        .unwrap()
+        .introspect(|_, visit| floats.extend(visit.downcast_ref::<f64>()));
+    assert_eq!(floats, vec![0.25]);
+}
+
+#[test]
+fn raw_value() {
+    let raw = RawValue::from_string("[true]".into()).unwrap();
+    assert_eq!(visits(&raw), vec![(crumbs(&[]), "RawValue")]);
+
+    let options = Options {
+        parse_raw_json: true,
+        ..Options::default()
+    };
+    assert_eq!(
+        visits_with(&raw, &options),
+        vec![
+            (crumbs(&[]), "RawValue"),
+            (crumbs(&[Variant("Parsed")]), "Value"),
+            (crumbs(&[Variant("Parsed"), Variant("Array")]), "Vec"),
+            (
+                crumbs(&[Variant("Parsed"), Variant("Array"), Index("0".into())]),
+                "Value"
+            ),
+            (
+                crumbs(&[
+                    Variant("Parsed"),
+                    Variant("Array"),
+                    Index("0".into()),
+                    Variant("Bool")
+                ]),
+                "bool"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn error() {
+    use serde_json::error::Category;
+
+    let error = serde_json::from_str::<Value>("[\n  tru").unwrap_err();
+    let mut fields = Vec::new();
+    error.introspect(|breadcrumbs, visit| {
+        if let Some(category) = visit.downcast_ref::<Category>() {
+            fields.push((breadcrumbs.clone(), format!("{:?}", category)));
+        }
+        if let Some(n) = visit.downcast_ref::<usize>() {
+            fields.push((breadcrumbs.clone(), n.to_string()));
+        }
+    });
+    assert_eq!(
+        fields,
+        vec![
+            (crumbs(&[Breadcrumb::Field("category")]), "Eof".to_string()),
+            (crumbs(&[Breadcrumb::Field("line")]), "2".to_string()),
+            (crumbs(&[Breadcrumb::Field("column")]), "5".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn string() {
+    assert_eq!(
+        visits(&json!("foo")),
+        vec![
+            (crumbs(&[]), "Value"),
+            (crumbs(&[Variant("String")]), "String"),
+        ]
+    );
+}
+
+#[test]
+fn array() {
+    assert_eq!(
+        visits(&json!([null, false])),
+        vec![
+            (crumbs(&[]), "Value"),
+            (crumbs(&[Variant("Array")]), "Vec"),
+            (crumbs(&[Variant("Array"), Index("0".into())]), "Value"),
+            (
+                crumbs(&[Variant("Array"), Index("0".into()), Variant("Null")]),
+                "()"
+            ),
+            (crumbs(&[Variant("Array"), Index("1".into())]), "Value"),
+            (
+                crumbs(&[Variant("Array"), Index("1".into()), Variant("Bool")]),
+                "bool"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn object() {
+    assert_eq!(
+        visits(&json!({"a": null, "b": "c"})),
+        vec![
+            (crumbs(&[]), "Value"),
+            (crumbs(&[Variant("Object")]), "Map"),
+            (crumbs(&[Variant("Object"), Index("a".into())]), "Value"),
+            (
+                crumbs(&[Variant("Object"), Index("a".into()), Variant("Null")]),
+                "()"
+            ),
+            (crumbs(&[Variant("Object"), Index("b".into())]), "Value"),
+            (
+                crumbs(&[Variant("Object"), Index("b".into()), Variant("String")]),
+                "String"
+            ),
+        ]
+    );
+}
@@ -0,0 +1,167 @@
+#![cfg(feature = "serde")]
+
+use serde::Serialize;
+use spectacle::{
+    format_path,
+    path::{self, Navigate},
+    serialize::{introspect_serialize, introspect_serialize_with, Placeholder, Serialized},
+    Breadcrumbs, Introspect, Options, Spectacle,
+};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Spectacle)]
+struct Inventory {
+    owner: Option<String>,
+    items: Vec<Item>,
+    counts: BTreeMap<String, (u8, bool)>,
+    id: Id,
+}
+
+#[derive(Serialize, Spectacle)]
+enum Item {
+    Empty,
+    Coin(u32),
+    Gem(char, f32),
+    Box { label: String, contents: Vec<u16> },
+}
+
+#[derive(Serialize, Spectacle)]
+struct Id(i64);
+
+fn inventory() -> Inventory {
+    let mut counts = BTreeMap::new();
+    counts.insert("apples".to_string(), (3, true));
+    Inventory {
+        owner: Some("sam".to_string()),
+        items: vec![
+            Item::Empty,
+            Item::Coin(5),
+            Item::Gem('x', 1.5),
+            Item::Box {
+                label: "misc".to_string(),
+                contents: vec![1, 2],
+            },
+        ],
+        counts,
+        id: Id(-7),
+    }
+}
+
+fn paths(introspect: impl FnOnce(&mut dyn FnMut(&Breadcrumbs, &dyn std::any::Any))) -> Vec<String> {
+    let mut paths = Vec::new();
+    introspect(&mut |breadcrumbs, _| paths.push(format_path(breadcrumbs)));
+    paths
+}
+
+#[test]
+fn matches_derived_breadcrumbs() {
+    let inventory = inventory();
+    for options in &[
+        Options::default(),
+        Options {
+            map_keys: true,
+            ..Options::default()
+        },
+    ] {
+        let derived = paths(|visit| inventory.introspect_with(options, visit));
+        let serialized = paths(|visit| {
+            introspect_serialize_with(&inventory, options, Breadcrumbs::new(), visit).unwrap()
+        });
+        assert_eq!(serialized, derived);
+    }
+}
+
+#[test]
+fn visits_primitives_and_placeholders() {
+    let mut visited = Vec::new();
+    introspect_serialize(&inventory(), |breadcrumbs, item| {
+        let description = if let Some(placeholder) = item.downcast_ref::<Placeholder>() {
+            format!("{:?}", placeholder)
+        } else if let Some(s) = item.downcast_ref::<String>() {
+            format!("{:?}", s)
+        } else if let Some(n) = item.downcast_ref::<i64>() {
+            n.to_string()
+        } else {
+            return;
+        };
+        visited.push(format!("{} {}", format_path(breadcrumbs), description));
+    })
+    .unwrap();
+    assert_eq!(
+        &visited[..4],
+        &[
+            r#"$ Struct("Inventory")"#,
+            "$.owner Some",
+            r#"$.owner::Some "sam""#,
+            "$.items Seq",
+        ]
+    );
+    assert!(visited
+        .contains(&r#"$.items[0] UnitVariant { name: "Item", variant: "Empty" }"#.to_string()));
+    assert!(visited
+        .contains(&r#"$.items[3] StructVariant { name: "Item", variant: "Box" }"#.to_string()));
+    assert!(visited.contains(&r#"$.counts["apples"] Tuple"#.to_string()));
+    assert!(visited.contains(&"$.id.0 -7".to_string()));
+}
+
+#[test]
+fn unsupported_keys_are_errors() {
+    let mut map = BTreeMap::new();
+    map.insert(vec![1], 2);
+    let error = introspect_serialize(&map, |_, _| {}).unwrap_err();
+    assert!(error.to_string().contains("map keys"));
+}
+
+#[derive(Spectacle, Navigate)]
+struct Wrapper {
+    name: String,
+    inventory: Serialized<Inventory>,
+}
+
+#[test]
+fn serialized_fields() {
+    let wrapper = Wrapper {
+        name: "w".to_string(),
+        inventory: Serialized(inventory()),
+    };
+    let mut paths = Vec::new();
+    wrapper.introspect(|breadcrumbs, _| paths.push(format_path(breadcrumbs)));
+    // the wrapper is visited, and its contents beneath it
+    assert_eq!(paths[2], "$.inventory");
+    assert_eq!(paths[3], "$.inventory.0");
+    assert!(paths.contains(&"$.inventory.0.items[3]::Box.contents[1]".to_string()));
+    assert!(paths.contains(&"$.inventory.0.id.0".to_string()));
+
+    // navigation agrees with the visits, but cannot reach the contents
+    let mut visits = Vec::new();
+    wrapper.introspect(|breadcrumbs, visit| visits.push((breadcrumbs.clone(), visit.type_id())));
+    for (breadcrumbs, type_id) in &visits[..3] {
+        assert_eq!(
+            path::get(&wrapper, breadcrumbs).unwrap().type_id(),
+            *type_id
+        );
+    }
+    assert!(path::get(&wrapper, &visits[3].0).is_err());
+}
+
+struct Failing;
+
+impl Serialize for Failing {
+    fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("cannot serialize"))
+    }
+}
+
+#[test]
+fn serialized_errors_are_visited() {
+    let mut errors = Vec::new();
+    (1_u8, Serialized(Failing)).introspect(|breadcrumbs, visit| {
+        if let Some(err) = visit.downcast_ref::<spectacle::serialize::Error>() {
+            errors.push((format_path(breadcrumbs), err.to_string()));
+        }
+    });
+    assert_eq!(
+        errors,
+        vec![("$.1.0".to_string(), "cannot serialize".to_string())]
+    );
+}
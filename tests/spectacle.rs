@@ -1,3 +1,5 @@
+#![allow(clippy::disallowed_names)]
+
 use spectacle::{Introspect, Spectacle};
 
 /// construct a state machine which verifies that we get the expected visits, of the
@@ -161,3 +163,226 @@ const TRIPLE: Triple<u8, u16, u32> = Triple(0, 1, 2);
 fn derives_non_conflicting_generic_ident() {
     expect_visits!(TRIPLE => Triple<u8, u16, u32>, 0 => u8, 1 => u16, 2 => u32);
 }
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Spectacle)]
+pub enum Key {
+    Named(&'static str),
+    Anonymous,
+}
+
+#[test]
+fn map_keys() {
+    use spectacle::{Breadcrumb, Options};
+    use std::collections::BTreeMap;
+
+    let mut map = BTreeMap::new();
+    map.insert(Key::Named("foo"), 1_u8);
+    map.insert(Key::Anonymous, 2_u8);
+
+    let mut keys = 0;
+    map.introspect(|_, visit| {
+        if visit.is::<Key>() {
+            keys += 1;
+        }
+    });
+    assert_eq!(keys, 0, "keys are not visited by default");
+
+    let mut visits = Vec::new();
+    let options = Options {
+        map_keys: true,
+        ..Options::default()
+    };
+    map.introspect_with(&options, |breadcrumbs, visit| {
+        if let Some(key) = visit.downcast_ref::<Key>() {
+            visits.push((breadcrumbs.clone(), format!("{:?}", key)));
+        }
+        if let Some(name) = visit.downcast_ref::<&'static str>() {
+            visits.push((breadcrumbs.clone(), name.to_string()));
+        }
+    });
+    assert_eq!(
+        visits,
+        vec![
+            (
+                vec![Breadcrumb::MapKey(r#"Named("foo")"#.into())].into(),
+                r#"Named("foo")"#.to_string()
+            ),
+            (
+                vec![
+                    Breadcrumb::MapKey(r#"Named("foo")"#.into()),
+                    Breadcrumb::Variant("Named"),
+                    Breadcrumb::TupleIndex(0),
+                ]
+                .into(),
+                "foo".to_string()
+            ),
+            (
+                vec![Breadcrumb::MapKey("Anonymous".into())].into(),
+                "Anonymous".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn stable_order() {
+    use spectacle::Options;
+    use std::collections::{HashMap, HashSet};
+
+    fn visit_order<T: Introspect>(t: &T) -> Vec<u32> {
+        let mut order = Vec::new();
+        let options = Options {
+            stable_order: true,
+            ..Options::default()
+        };
+        t.introspect_with(&options, |_, visit| {
+            if let Some(n) = visit.downcast_ref::<u32>() {
+                order.push(*n);
+            }
+        });
+        order
+    }
+
+    // every map and set gets its own random hash keys, so unstable iteration
+    // orders would very likely differ between these
+    let maps: Vec<HashMap<String, u32>> = (0..8)
+        .map(|_| (0..32).map(|n| (format!("key {:02}", n), n)).collect())
+        .collect();
+    for map in &maps {
+        assert_eq!(visit_order(map), (0..32).collect::<Vec<_>>());
+    }
+
+    // keys of std types are ordered by `Ord`, not by their formatted index
+    let maps: Vec<HashMap<u32, u32>> = (0..8).map(|_| (0..32).map(|n| (n, n)).collect()).collect();
+    for map in &maps {
+        assert_eq!(visit_order(map), (0..32).collect::<Vec<_>>());
+    }
+
+    let sets: Vec<HashSet<u32>> = (0..8).map(|_| (0..32).collect()).collect();
+    let expect = visit_order(&sets[0]);
+    for set in &sets {
+        assert_eq!(visit_order(set), expect);
+    }
+
+    // members are hashed by a fixed algorithm, so their trails never change
+    let set: HashSet<u32> = std::iter::once(7).collect();
+    let mut paths = Vec::new();
+    set.introspect(|breadcrumbs, _| paths.push(spectacle::format_path(breadcrumbs)));
+    assert_eq!(paths, vec!["$", "${6d3572669b2cde42}"]);
+}
+
+#[test]
+fn set_members_are_distinguishable() {
+    use spectacle::{Breadcrumb, Breadcrumbs};
+    use std::collections::{BTreeSet, BinaryHeap, HashSet};
+
+    fn member_trails<T: Introspect>(t: &T) -> Vec<Breadcrumbs> {
+        let mut trails = Vec::new();
+        t.introspect(|breadcrumbs, visit| {
+            if visit.is::<u32>() {
+                trails.push(breadcrumbs.clone());
+            }
+        });
+        trails
+    }
+
+    let btree: BTreeSet<u32> = (0..4).collect();
+    assert_eq!(
+        member_trails(&btree),
+        (0..4)
+            .map(|n| Breadcrumbs::unit(Breadcrumb::SetMember(n.to_string())))
+            .collect::<Vec<_>>()
+    );
+
+    let heap: BinaryHeap<u32> = (0..4).collect();
+    let trails = member_trails(&heap);
+    assert_eq!(trails.len(), 4);
+    assert_eq!(
+        trails
+            .iter()
+            .collect::<std::collections::HashSet<_>>()
+            .len(),
+        4
+    );
+
+    // hash set members keep the same trail no matter how the set was built
+    let forward: HashSet<u32> = (0..64).collect();
+    let backward: HashSet<u32> = (0..64).rev().collect();
+    let forward_trails = member_trails(&forward)
+        .into_iter()
+        .collect::<std::collections::HashSet<_>>();
+    assert_eq!(forward_trails.len(), 64);
+    assert_eq!(
+        forward_trails,
+        member_trails(&backward).into_iter().collect()
+    );
+
+    // members whose hashes collide are distinguished by a suffix
+    #[derive(PartialEq, Eq, Spectacle)]
+    struct Colliding(u32);
+
+    impl std::hash::Hash for Colliding {
+        fn hash<H: std::hash::Hasher>(&self, _state: &mut H) {}
+    }
+
+    let colliding: HashSet<Colliding> = (0..3).map(Colliding).collect();
+    let mut members: Vec<_> = member_trails(&colliding)
+        .into_iter()
+        .map(|trail| trail[0].clone())
+        .collect();
+    members.sort();
+    assert_eq!(
+        members,
+        vec![
+            Breadcrumb::SetMember("cbf29ce484222325".into()),
+            Breadcrumb::SetMember("cbf29ce484222325#1".into()),
+            Breadcrumb::SetMember("cbf29ce484222325#2".into()),
+        ]
+    );
+}
+
+#[test]
+fn format_path() {
+    let mut paths = Vec::new();
+    Some((SIMPLE_STRUCT, vec![UNIT_ENUM])).introspect(|breadcrumbs, _| {
+        paths.push(spectacle::format_path(breadcrumbs));
+    });
+    assert_eq!(
+        paths,
+        vec![
+            "$",
+            "$::Some",
+            "$::Some.0",
+            "$::Some.0.a",
+            "$::Some.0.b",
+            "$::Some.1",
+            "$::Some.1[0]",
+        ]
+    );
+}
+
+// implements only `Introspect`, not `TypeInfo` or `Navigate`
+#[derive(Debug, PartialEq)]
+struct Opaque(u8);
+
+impl Introspect for Opaque {
+    fn introspect_from<F>(&self, breadcrumbs: spectacle::Breadcrumbs, mut visit: F)
+    where
+        F: FnMut(&spectacle::Breadcrumbs, &dyn std::any::Any),
+    {
+        visit(&breadcrumbs, self);
+    }
+}
+
+#[derive(Debug, PartialEq, Spectacle)]
+struct HandImplementedField {
+    opaque: Opaque,
+}
+
+#[test]
+fn hand_implemented_field() {
+    expect_visits!(
+        HandImplementedField { opaque: Opaque(1) } => HandImplementedField,
+        Opaque(1) => Opaque,
+    );
+}
@@ -0,0 +1,125 @@
+#![cfg(feature = "serde-json")]
+
+use serde_json::{json, Value};
+use spectacle::{
+    json::stream::{introspect_documents, introspect_reader, introspect_str},
+    Breadcrumbs, Introspect, Options,
+};
+
+// each breadcrumb trail visited, with a description of what was visited there
+fn materialized(value: &Value, options: &Options) -> Vec<(Breadcrumbs, String)> {
+    let mut visited = Vec::new();
+    value.introspect_with(options, |breadcrumbs, visit| {
+        visited.push((breadcrumbs.clone(), describe(visit)));
+    });
+    visited
+}
+
+// the type visited, and the value if it is a scalar; the contents of arrays
+// and objects are not compared, as they are empty when streamed
+fn describe(visit: &dyn std::any::Any) -> String {
+    if let Some(value) = visit.downcast_ref::<Value>() {
+        match value {
+            Value::Array(_) => "Value::Array".to_string(),
+            Value::Object(_) => "Value::Object".to_string(),
+            scalar => format!("Value {}", scalar),
+        }
+    } else if visit.is::<Vec<Value>>() {
+        "Vec<Value>".to_string()
+    } else if visit.is::<serde_json::Map<String, Value>>() {
+        "Map<String, Value>".to_string()
+    } else if let Some(b) = visit.downcast_ref::<bool>() {
+        format!("bool {}", b)
+    } else if let Some(n) = visit.downcast_ref::<serde_json::Number>() {
+        format!("Number {}", n)
+    } else if let Some(s) = visit.downcast_ref::<String>() {
+        format!("String {}", s)
+    } else if let Some(n) = visit.downcast_ref::<u64>() {
+        format!("u64 {}", n)
+    } else if let Some(n) = visit.downcast_ref::<i64>() {
+        format!("i64 {}", n)
+    } else if let Some(n) = visit.downcast_ref::<f64>() {
+        format!("f64 {}", n)
+    } else if visit.is::<()>() {
+        "()".to_string()
+    } else {
+        format!("{:?}", visit.type_id())
+    }
+}
+
+#[test]
+fn same_breadcrumbs_as_value() {
+    let document = json!({
+        "null": null,
+        "bool": true,
+        "numbers": [1, -2, 3.5, 18446744073709551615u64],
+        "string": "s",
+        "nested": {"empty": {}, "list": [[], [{"a": "b"}]]},
+    });
+    let json = document.to_string();
+
+    for options in [
+        Options::default(),
+        Options {
+            map_keys: true,
+            ..Options::default()
+        },
+    ] {
+        let mut streamed = Vec::new();
+        introspect_str(&json, &options, |breadcrumbs, visit| {
+            streamed.push((breadcrumbs.clone(), describe(visit)));
+        })
+        .unwrap();
+        assert_eq!(streamed, materialized(&document, &options));
+    }
+}
+
+#[test]
+fn containers_are_empty_values() {
+    let mut visited = Vec::new();
+    introspect_reader("[1, \"a\"]".as_bytes(), &Options::default(), |_, visit| {
+        if let Some(value) = visit.downcast_ref::<Value>() {
+            visited.push(value.clone());
+        }
+    })
+    .unwrap();
+    assert_eq!(visited, vec![json!([]), json!(1), json!("a")]);
+}
+
+#[test]
+fn documents() {
+    let lines = "{\"a\": 1}\n{\"a\": 2}\n\n[true]\n";
+    let mut roots = 0;
+    let mut streamed = Vec::new();
+    let count = introspect_documents(
+        lines.as_bytes(),
+        &Options::default(),
+        |breadcrumbs, visit| {
+            if breadcrumbs.is_empty() {
+                roots += 1;
+            }
+            streamed.push((breadcrumbs.clone(), describe(visit)));
+        },
+    )
+    .unwrap();
+    assert_eq!(count, 3);
+    assert_eq!(roots, 3);
+
+    let expected: Vec<_> = [json!({"a": 1}), json!({"a": 2}), json!([true])]
+        .iter()
+        .flat_map(|document| materialized(document, &Options::default()))
+        .collect();
+    assert_eq!(streamed, expected);
+}
+
+#[test]
+fn errors() {
+    let options = Options::default();
+    assert!(introspect_str("[1, 2", &options, |_, _| {}).is_err());
+    assert!(introspect_str("1 2", &options, |_, _| {}).is_err());
+    assert!(introspect_documents("1 2 {".as_bytes(), &options, |_, _| {}).is_err());
+    assert_eq!(
+        introspect_documents("  \n".as_bytes(), &options, |_, _| {}).unwrap(),
+        0
+    );
+}
@@ -0,0 +1,188 @@
+use spectacle::{
+    type_info::{self, TypeKind, TypeRef},
+    Breadcrumb, Breadcrumbs, Introspect, Spectacle, TypeInfo,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Spectacle)]
+struct Pair<A, B>(A, B);
+
+#[derive(Spectacle)]
+#[allow(dead_code)]
+enum Node {
+    Leaf(u8),
+    Branch {
+        children: Vec<Node>,
+        tags: BTreeSet<String>,
+    },
+}
+
+#[derive(Spectacle)]
+#[allow(dead_code)]
+enum Shape {
+    Point,
+    Circle(f32),
+    Rect { width: u8, height: u8 },
+}
+
+#[derive(Spectacle)]
+struct Document {
+    title: Option<String>,
+    shape: Shape,
+    meta: BTreeMap<String, Pair<bool, [u16; 2]>>,
+}
+
+fn normalize(breadcrumbs: &Breadcrumbs) -> Breadcrumbs {
+    breadcrumbs
+        .iter()
+        .map(|breadcrumb| match breadcrumb {
+            Breadcrumb::Index(_) => Breadcrumb::Index("*".to_string()),
+            Breadcrumb::SetMember(_) => Breadcrumb::SetMember("*".to_string()),
+            other => other.clone(),
+        })
+        .collect()
+}
+
+#[test]
+fn generics_and_arity() {
+    let pair = Pair::<u8, Vec<char>>::type_info();
+    assert_eq!(
+        pair.generics,
+        vec![TypeRef::of::<u8>(), TypeRef::of::<Vec<char>>()]
+    );
+    assert_eq!(pair.arity(), Some(2));
+    assert_eq!(Document::type_info().generics, vec![]);
+    assert_eq!(Document::type_info().arity(), None);
+
+    let tuple = <(u8, bool, String)>::type_info();
+    assert_eq!(tuple.arity(), Some(3));
+    assert_eq!(tuple.generics.len(), 3);
+    assert_eq!(
+        BTreeMap::<String, i64>::type_info().generics,
+        vec![TypeRef::of::<String>(), TypeRef::of::<i64>()]
+    );
+    assert_eq!(<()>::type_info().arity(), Some(0));
+}
+
+#[test]
+fn paths_are_enumerated() {
+    let paths: Vec<String> = type_info::paths::<Node>()
+        .iter()
+        .map(|(breadcrumbs, ty)| format!("{} {}", spectacle::format_path(breadcrumbs), ty.name))
+        .collect();
+    assert_eq!(
+        paths,
+        vec![
+            "$ type_info::Node",
+            "$::Leaf.0 u8",
+            "$::Branch.children alloc::vec::Vec<type_info::Node>",
+            "$::Branch.children[*] type_info::Node",
+            "$::Branch.tags alloc::collections::btree::set::BTreeSet<alloc::string::String>",
+            "$::Branch.tags{*} alloc::string::String",
+        ]
+    );
+    assert!(matches!(
+        type_info::paths::<Node>().last().unwrap().1.kind,
+        TypeKind::Primitive(_)
+    ));
+}
+
+#[test]
+fn paths_match_introspection() {
+    let document = Document {
+        title: Some("notes".to_string()),
+        shape: Shape::Circle(1.0),
+        meta: vec![("a".to_string(), Pair(true, [1, 2]))]
+            .into_iter()
+            .collect(),
+    };
+    let paths: BTreeSet<Breadcrumbs> = type_info::paths::<Document>()
+        .into_iter()
+        .map(|(breadcrumbs, _)| breadcrumbs)
+        .collect();
+
+    let mut visited = BTreeSet::new();
+    document.introspect(|breadcrumbs, _| {
+        visited.insert(normalize(breadcrumbs));
+    });
+    // a value visits one variant of each enum
+    let missing: Vec<String> = paths
+        .difference(&visited)
+        .map(spectacle::format_path)
+        .collect();
+    assert_eq!(
+        missing,
+        vec!["$.shape::Rect.height", "$.shape::Rect.width",]
+    );
+    assert!(visited.is_subset(&paths));
+}
+
+#[derive(Spectacle)]
+#[allow(dead_code)]
+enum Never {}
+
+#[derive(Spectacle)]
+#[allow(dead_code)]
+enum Either<A, B> {
+    Left(A),
+    Right(B),
+}
+
+#[test]
+fn active_variants() {
+    let active = |ty: type_info::Type, value: &dyn std::any::Any| {
+        ty.active_variant.and_then(|active| active.of(value))
+    };
+    assert_eq!(active(Shape::type_info(), &Shape::Point), Some("Point"));
+    assert_eq!(
+        active(
+            Shape::type_info(),
+            &Shape::Rect {
+                width: 1,
+                height: 2
+            }
+        ),
+        Some("Rect")
+    );
+    assert_eq!(
+        active(
+            Either::<u8, char>::type_info(),
+            &Either::<u8, char>::Right('x')
+        ),
+        Some("Right")
+    );
+    // a value of another type has no variant of this one
+    assert_eq!(active(Shape::type_info(), &1_u8), None);
+    assert_eq!(active(Never::type_info(), &Shape::Point), None);
+    assert!(Document::type_info().active_variant.is_none());
+}
+
+// implements only `Introspect`, not `TypeInfo`
+struct Handle;
+
+impl Introspect for Handle {
+    fn introspect_from<F>(&self, breadcrumbs: Breadcrumbs, mut visit: F)
+    where
+        F: FnMut(&Breadcrumbs, &dyn std::any::Any),
+    {
+        visit(&breadcrumbs, self);
+    }
+}
+
+#[derive(Spectacle)]
+#[allow(dead_code)]
+struct Connection(Handle, u8);
+
+#[test]
+fn fields_without_type_info_are_opaque() {
+    let paths: Vec<String> = type_info::paths::<Connection>()
+        .iter()
+        .map(|(breadcrumbs, ty)| format!("{} {}", spectacle::format_path(breadcrumbs), ty.name))
+        .collect();
+    assert_eq!(
+        paths,
+        vec!["$ type_info::Connection", "$.0 type_info::Handle", "$.1 u8"]
+    );
+    let handle = type_info::paths::<Connection>().remove(1).1;
+    assert!(matches!(handle.kind, TypeKind::Opaque));
+}
@@ -0,0 +1,184 @@
+#![cfg(feature = "serde-json")]
+
+use serde::Serialize;
+use serde_json::json;
+use spectacle::{
+    json::{json_schema, validate::Validator},
+    Breadcrumb, Spectacle,
+};
+
+#[test]
+fn violations_are_located() {
+    let validator = Validator::new(json!({
+        "type": "object",
+        "properties": {
+            "name": {"type": "string", "minLength": 1},
+            "tags": {"type": "array", "items": {"$ref": "#/$defs/tag"}, "uniqueItems": true},
+            "size": {"enum": [1, 2, 3]},
+        },
+        "required": ["name", "id"],
+        "additionalProperties": false,
+        "$defs": {
+            "tag": {"type": "string", "maxLength": 3},
+        },
+    }));
+
+    let violations = validator.validate(&json!({
+        "name": "",
+        "tags": ["ok", "toolong", 5, "ok"],
+        "size": 2.0,
+        "a/b": null,
+    }));
+    let found: Vec<_> = violations
+        .iter()
+        .map(|violation| (violation.pointer(), violation.keyword))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("".to_string(), "required"),
+            ("/a~1b".to_string(), "additionalProperties"),
+            ("/name".to_string(), "minLength"),
+            ("/tags".to_string(), "uniqueItems"),
+            ("/tags/1".to_string(), "maxLength"),
+            ("/tags/2".to_string(), "type"),
+        ]
+    );
+    let tag = &violations[5];
+    assert_eq!(
+        tag.breadcrumbs,
+        vec![
+            Breadcrumb::Variant("Object"),
+            Breadcrumb::Index("tags".into()),
+            Breadcrumb::Variant("Array"),
+            Breadcrumb::Index("2".into()),
+        ]
+        .into_iter()
+        .collect()
+    );
+    assert_eq!(tag.to_string(), "$.tags[2]: expected string, found integer");
+}
+
+#[test]
+fn combinators() {
+    let validator = Validator::new(json!({
+        "oneOf": [{"type": "integer"}, {"minimum": 0}],
+        "not": {"const": 3},
+        "if": {"type": "integer"},
+        "then": {"multipleOf": 0.5},
+        "else": {"exclusiveMaximum": 10},
+    }));
+    assert!(validator.is_valid(&json!(-1)));
+    assert!(validator.is_valid(&json!(2.5)));
+    let keywords = |value| {
+        validator
+            .validate(&value)
+            .iter()
+            .map(|violation| violation.keyword)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(keywords(json!(3)), vec!["oneOf", "not"]);
+    assert_eq!(keywords(json!(-0.5)), vec!["oneOf"]);
+    assert_eq!(keywords(json!(12.5)), vec!["exclusiveMaximum"]);
+    assert_eq!(keywords(json!(4)), vec!["oneOf"]);
+    assert!(validator.is_valid(&json!("x")));
+}
+
+#[test]
+fn pattern_properties() {
+    // members matching a pattern are not additional, and patterns are ignored
+    let validator = Validator::new(json!({
+        "properties": {"name": {"type": "string"}},
+        "patternProperties": {"^x-": {"type": "string"}},
+        "additionalProperties": false,
+    }));
+    assert!(validator.is_valid(&json!({"name": "a", "x-extra": "b"})));
+    assert!(!validator.is_valid(&json!({"name": 1})));
+}
+
+#[derive(Spectacle, Serialize)]
+struct Point {
+    x: u8,
+    label: Option<String>,
+    shape: Shape,
+}
+
+#[derive(Spectacle, Serialize)]
+enum Shape {
+    Dot,
+    Circle { radius: f64 },
+}
+
+fn points() -> Vec<Point> {
+    vec![
+        Point {
+            x: 1,
+            label: None,
+            shape: Shape::Dot,
+        },
+        Point {
+            x: 255,
+            label: Some("p".into()),
+            shape: Shape::Circle { radius: 1.5 },
+        },
+    ]
+}
+
+#[test]
+fn exported_schema_from_file() {
+    let path = std::env::temp_dir().join(format!("spectacle-validate-{}.json", std::process::id()));
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "x": {"type": "integer", "minimum": 0, "maximum": 255},
+            "label": {"type": ["string", "null"]},
+            "shape": {"oneOf": [
+                {"const": "Dot"},
+                {
+                    "type": "object",
+                    "properties": {
+                        "Circle": {
+                            "type": "object",
+                            "properties": {"radius": {"type": "number"}},
+                            "required": ["radius"],
+                        },
+                    },
+                    "required": ["Circle"],
+                    "additionalProperties": false,
+                },
+            ]},
+        },
+        "required": ["x", "shape"],
+    });
+    std::fs::write(&path, schema.to_string()).unwrap();
+    let validator = Validator::from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    for point in &points() {
+        assert_eq!(validator.validate_serialize(point).unwrap(), vec![]);
+    }
+
+    let violations = validator.validate(&json!({"x": 256, "shape": {"Square": {}}}));
+    let found: Vec<_> = violations
+        .iter()
+        .map(|violation| (violation.pointer(), violation.keyword))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("/shape".to_string(), "oneOf"),
+            ("/x".to_string(), "maximum"),
+        ]
+    );
+}
+
+#[test]
+fn exported_type_schema() {
+    // the schema of a type accepts its serialized values
+    let validator = Validator::new(json_schema::from_type::<Point>());
+    for point in &points() {
+        assert_eq!(validator.validate_serialize(point).unwrap(), vec![]);
+    }
+    assert!(!validator.is_valid(&json!({"x": -1, "shape": "Dot"})));
+    assert!(!validator.is_valid(&json!({"x": 1, "shape": "Square"})));
+}